@@ -0,0 +1,42 @@
+-- Sends a wave of reinforcements into the solar system a few seconds after
+-- the last of its hostile Brews is destroyed.
+local solar_system = 0
+local wave_delay = 3.0
+local wave_timer = nil
+local wave_sent = false
+
+local function hostiles_left(area)
+  local count = 0
+  for _, id in ipairs(area_entities(area)) do
+    if entity_hostility(id) == "Hostile" then
+      count = count + 1
+    end
+  end
+  return count
+end
+
+function solar_system_tick(delta_time)
+  if wave_sent then
+    return
+  end
+
+  if wave_timer == nil then
+    if hostiles_left(solar_system) == 0 then
+      wave_timer = wave_delay
+    end
+    return
+  end
+
+  wave_timer = wave_timer - delta_time
+  if wave_timer <= 0.0 then
+    for i = 0, 2 do
+      local x = -800.0 + random(-100.0, 100.0)
+      local y = 200.0 + i*300.0
+      local brew = spawn_entity(solar_system, "Brew", x, y, "Hostile", "StateMachine", "SingleShot")
+      apply_buff(brew, "MaxSpeedBuff")
+    end
+    wave_sent = true
+  end
+end
+
+on_area_tick(solar_system, "solar_system_tick")
@@ -0,0 +1,28 @@
+-- Circles the target at a distance, firing each ability as soon as it is ready, and waits
+-- where it is while there is nothing in sight.
+-- The same behaviour as AbilitySpamAi, as a starting point for scripted enemies.
+local orbit_distance = 400.0
+
+function update(delta_time)
+  if target == nil then
+    return
+  end
+
+  local dx = target.x - ship.x
+  local dy = target.y - ship.y
+  local distance = math.sqrt(dx*dx + dy*dy)
+
+  if distance < orbit_distance then
+    -- rotate 90 degrees to circle instead of closing in
+    dx, dy = -dy, dx
+  end
+
+  accelerate(dx, dy)
+  face(target.x, target.y)
+
+  for i, cooldown in ipairs(cooldowns) do
+    if cooldown <= 0.0 then
+      activate(i)
+    end
+  end
+end
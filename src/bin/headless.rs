@@ -0,0 +1,321 @@
+extern crate acwars;
+extern crate rand;
+extern crate hlua;
+extern crate parking_lot;
+
+use acwars::DELTA_STEP;
+use acwars::modules::world::{self, World};
+use acwars::modules::entities::{self, Ship, BoxEntity, MutexEntity};
+use acwars::modules::projectiles::BoxProjectile;
+use acwars::modules::areas::{self, BoxArea, BenchmarkArea};
+use acwars::modules::spatial_hash::{SpatialHash, SpatialObject, LinearSpatialHash};
+use acwars::modules::broadphase::BroadphaseKind;
+use acwars::modules::replay::Replay;
+use acwars::modules::player::InputState;
+
+use acwars::cgmath::Vector2;
+
+use hlua::Lua;
+
+use parking_lot::Mutex;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use std::env;
+use std::process;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const DEFAULT_TICKS: u64 = 10000;
+const INPUT_CHANGE_TICKS: u64 = 250;
+
+fn usage() {
+  println!("usage: headless run [ticks] [seed]");
+  println!("       headless determinism [ticks] [seed]");
+  println!("       headless replay <file>");
+  println!("       headless spatial-hash [ticks]");
+  println!("       headless backends [ticks]");
+}
+
+fn window_size() -> Vector2<f32> {
+  Vector2::new(1280.0, 1080.0)
+}
+
+fn new_world(seed: u64) -> World {
+  match World::battle(areas::DEFAULT_LEVEL, Box::new(Ship::new(Vector2::new(0.0, 0.0))), window_size()) {
+    Ok(world) => world.with_seed(seed).deterministic(),
+    Err(e) => {
+      println!("failed to load level {}: {}", areas::DEFAULT_LEVEL, e);
+      process::exit(1);
+    }
+  }
+}
+
+// Inputs a bot would give, changing every INPUT_CHANGE_TICKS, generated from its own seed
+// so the stream is identical for every world it drives.
+fn scripted_inputs(seed: u64, ticks: u64) -> Vec<InputState> {
+  let mut rng = StdRng::seed_from_u64(seed);
+  let dim = window_size();
+  
+  let mut inputs = Vec::with_capacity(ticks as usize);
+  let mut state = InputState::new();
+  for tick in 0..ticks {
+    if tick % INPUT_CHANGE_TICKS == 0 {
+      state.mouse_pos = Vector2::new(rng.gen_range(0.0, dim.x), rng.gen_range(0.0, dim.y));
+      state.left_mouse = rng.gen_bool(0.5);
+      state.right_mouse = rng.gen_bool(0.5);
+      state.q_pressed = rng.gen_bool(0.1);
+      state.w_pressed = rng.gen_bool(0.1);
+      state.e_pressed = rng.gen_bool(0.1);
+      state.r_pressed = rng.gen_bool(0.1);
+    }
+    inputs.push(state);
+  }
+  
+  inputs
+}
+
+fn new_lua() -> Lua<'static> {
+  let mut lua = Lua::new();
+  lua.openlibs();
+  lua
+}
+
+fn run_world(world: &mut World, inputs: &Vec<InputState>) {
+  let mut lua = new_lua();
+  for input_state in inputs {
+    world.update(input_state, Some(&mut lua), DELTA_STEP);
+    if world.ship_destroyed() {
+      break;
+    }
+  }
+}
+
+fn print_world(world: &World) {
+  let ship = world.ship().lock();
+  let mut entities = 0;
+  for area in world.areas() {
+    entities += area.entities().len();
+  }
+  
+  println!("seed: {}", world.seed());
+  println!("ticks: {}", world.ticks());
+  println!("ship position: ({}, {})", ship.position().x, ship.position().y);
+  println!("ship health: {}", ship.health());
+  println!("entities: {}", entities);
+  println!("projectiles: {}", world.projectiles().len());
+}
+
+fn run(ticks: u64, seed: u64) -> i32 {
+  let mut world = new_world(seed);
+  run_world(&mut world, &scripted_inputs(seed, ticks));
+  print_world(&world);
+  
+  0
+}
+
+// Plays a saved battle through the same simulation without a window, so a replay from a
+// bug report or an old match can be checked against the current balance.
+fn replay(path: &str) -> i32 {
+  let mut replay = match Replay::load(path) {
+    Ok(replay) => replay,
+    Err(e) => {
+      println!("replay: failed to load {}: {}", path, e);
+      return 1;
+    }
+  };
+  
+  let ship = match entities::selectable_ship(&replay.ship()) {
+    Some(ship) => ship,
+    None => {
+      println!("replay: unknown ship {}", replay.ship());
+      return 1;
+    }
+  };
+  
+  let mut world = match World::battle(&replay.level(), ship, replay.window_size()) {
+    Ok(world) => world.with_seed(replay.seed()).deterministic(),
+    Err(e) => {
+      println!("replay: failed to load level {}: {}", replay.level(), e);
+      return 1;
+    }
+  };
+  let mut lua = new_lua();
+  while let Some(tick) = replay.next_input() {
+    world.replay_tick(&tick, Some(&mut lua), DELTA_STEP);
+  }
+  
+  println!("ship: {}", replay.ship());
+  println!("level: {}", replay.level());
+  print_world(&world);
+  println!("ship destroyed: {}", world.ship_destroyed());
+  
+  0
+}
+
+fn millis(duration: Duration) -> f64 {
+  duration.as_secs() as f64*1000.0 + duration.subsec_nanos() as f64/1_000_000.0
+}
+
+// Runs the 100 Brew BenchmarkArea and times the same broadphase work every tick with the
+// hash map SpatialHash and the old linear container.
+fn spatial_hash_benchmark(ticks: u64) -> i32 {
+  let benchmark: BoxArea = Box::new(BenchmarkArea::new(Vector2::new(0.0, 0.0), Vector2::new(20000.0, 20000.0), 10));
+  let ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)).with_health(1500000000.0));
+  let mut world = World::new(vec!(benchmark), ship, window_size()).deterministic();
+  
+  let cell_size = 30.0;
+  let mut spatial_hash: SpatialHash<MutexEntity> = SpatialHash::new(cell_size);
+  let mut linear = LinearSpatialHash::new(cell_size);
+  
+  let mut hash_time = Duration::new(0, 0);
+  let mut linear_time = Duration::new(0, 0);
+  let mut hash_found = 0;
+  let mut linear_found = 0;
+  
+  for input_state in &scripted_inputs(world::DEFAULT_SEED, ticks) {
+    world.update(input_state, None, DELTA_STEP);
+    
+    let entities = world_entities(&world);
+    let projectiles: Vec<BoxProjectile> = world.projectiles().iter().map(|projectile| projectile.lock().clone()).collect();
+    
+    let start = Instant::now();
+    spatial_hash.clear();
+    for entity in &entities {
+      spatial_hash.insert_object_for_point(Arc::clone(entity));
+    }
+    for projectile in &projectiles {
+      hash_found += spatial_hash.retrieve_objects(projectile).len();
+    }
+    hash_found += spatial_hash.retrieve_possible_collisions().len();
+    hash_time += start.elapsed();
+    
+    let start = Instant::now();
+    linear.clear();
+    for entity in &entities {
+      linear.insert_object_for_point(Arc::clone(entity));
+    }
+    for projectile in &projectiles {
+      linear_found += linear.retrieve_objects(projectile).len();
+    }
+    linear_found += linear.retrieve_possible_entity_collisions().len();
+    linear_time += start.elapsed();
+  }
+  
+  // the linear container reports an object once per shared cell, the hash once overall
+  println!("ticks: {}", world.ticks());
+  println!("projectiles at end: {}", world.projectiles().len());
+  println!("hash map:  {:.3}ms total, {:.4}ms per tick, {} candidates", millis(hash_time), millis(hash_time)/ticks.max(1) as f64, hash_found);
+  println!("linear:    {:.3}ms total, {:.4}ms per tick, {} candidates", millis(linear_time), millis(linear_time)/ticks.max(1) as f64, linear_found);
+  
+  0
+}
+
+fn world_entities(world: &World) -> Vec<MutexEntity> {
+  let mut entities: Vec<MutexEntity> = Vec::new();
+  for area in world.areas() {
+    entities.append(&mut area.entities());
+  }
+  entities.push(Arc::clone(world.ship()));
+  
+  entities
+}
+
+// Records every tick of the 100 Brew BenchmarkArea, then times each broadphase building
+// and querying those same ticks.
+fn backends_benchmark(ticks: u64) -> i32 {
+  let benchmark: BoxArea = Box::new(BenchmarkArea::new(Vector2::new(0.0, 0.0), Vector2::new(20000.0, 20000.0), 10));
+  let ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)).with_health(1500000000.0));
+  let mut world = World::new(vec!(benchmark), ship, window_size()).deterministic();
+  
+  let mut frames = Vec::new();
+  for input_state in &scripted_inputs(world::DEFAULT_SEED, ticks) {
+    world.update(input_state, None, DELTA_STEP);
+    
+    // copies, so later ticks don't move what earlier frames recorded
+    let entities: Vec<MutexEntity> = world_entities(&world).iter().map(|entity| Arc::new(Mutex::new(entity.lock().clone()))).collect();
+    let projectiles: Vec<BoxProjectile> = world.projectiles().iter().map(|projectile| projectile.lock().clone()).collect();
+    frames.push((entities, projectiles));
+  }
+  
+  println!("ticks: {}", frames.len());
+  for kind in BroadphaseKind::all() {
+    let mut backend = kind.create();
+    let mut candidates = 0;
+    let mut pairs = 0;
+    
+    let start = Instant::now();
+    for (entities, projectiles) in &frames {
+      backend.build(entities);
+      for projectile in projectiles {
+        let (min, max) = projectile.bounds();
+        candidates += backend.query(min, max).len();
+      }
+      pairs += backend.pairs().len();
+      backend.clear();
+    }
+    let time = start.elapsed();
+    
+    println!("{:<13} {:.3}ms total, {:.4}ms per tick, {} projectile candidates, {} entity pairs",
+             kind.name(), millis(time), millis(time)/frames.len().max(1) as f64, candidates, pairs);
+  }
+  
+  0
+}
+
+// Steps two worlds built from the same seed with the same inputs and fails if any entity
+// position, velocity or health differs by even a single bit.
+fn determinism(ticks: u64, seed: u64) -> i32 {
+  let inputs = scripted_inputs(seed, ticks);
+  
+  let mut first = new_world(seed);
+  let mut second = new_world(seed);
+  run_world(&mut first, &inputs);
+  run_world(&mut second, &inputs);
+  
+  let first_states = first.entity_states();
+  let second_states = second.entity_states();
+  
+  if first.ticks() != second.ticks() || first_states.len() != second_states.len() {
+    println!("determinism: FAILED, worlds ran {} and {} ticks with {} and {} entities", 
+             first.ticks(), second.ticks(), first_states.len(), second_states.len());
+    return 1;
+  }
+  
+  for i in 0..first_states.len() {
+    let (pos_a, vel_a, health_a) = first_states[i];
+    let (pos_b, vel_b, health_b) = second_states[i];
+    if pos_a.x.to_bits() != pos_b.x.to_bits() || pos_a.y.to_bits() != pos_b.y.to_bits() ||
+       vel_a.x.to_bits() != vel_b.x.to_bits() || vel_a.y.to_bits() != vel_b.y.to_bits() ||
+       health_a.to_bits() != health_b.to_bits() {
+      println!("determinism: FAILED, entity {} diverged after {} ticks", i, first.ticks());
+      return 1;
+    }
+  }
+  
+  println!("determinism: ok, {} entities identical after {} ticks with seed {}", first_states.len(), first.ticks(), seed);
+  
+  0
+}
+
+fn main() {
+  let args: Vec<String> = env::args().collect();
+  
+  let ticks = args.get(2).and_then(|t| t.parse::<u64>().ok()).unwrap_or(DEFAULT_TICKS);
+  let seed = args.get(3).and_then(|s| s.parse::<u64>().ok()).unwrap_or(world::DEFAULT_SEED);
+  
+  let code = match args.get(1).map(|s| s.as_str()) {
+    Some("run") => run(ticks, seed),
+    Some("determinism") => determinism(ticks, seed),
+    Some("replay") if args.len() > 2 => replay(&args[2]),
+    Some("spatial-hash") => spatial_hash_benchmark(ticks),
+    Some("backends") => backends_benchmark(ticks),
+    _ => {
+      usage();
+      1
+    },
+  };
+  
+  process::exit(code);
+}
@@ -0,0 +1,15 @@
+extern crate maat_graphics;
+extern crate maat_input_handler;
+extern crate maat_gui;
+extern crate rand;
+extern crate hlua;
+extern crate parking_lot;
+extern crate serde;
+extern crate ron;
+
+pub use maat_graphics::winit;
+pub use maat_graphics::cgmath;
+
+pub mod modules;
+
+pub const DELTA_STEP: f32 = 0.001;
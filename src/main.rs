@@ -1,32 +1,29 @@
+extern crate acwars;
 extern crate maat_graphics;
-extern crate maat_input_handler;
-extern crate maat_gui;
-extern crate rand;
 extern crate hlua;
-extern crate parking_lot;
 
-pub use maat_graphics::winit;
-pub use maat_graphics::cgmath;
-
-mod modules;
-
-use crate::modules::scenes::Scene;
-use crate::modules::scenes::LoadScreen;
+use acwars::winit;
+use acwars::DELTA_STEP;
+use acwars::modules::scenes::Scene;
+use acwars::modules::scenes::LoadScreen;
+use acwars::modules::replay::Replay;
+use acwars::modules::entities::{ShipDefinition, SHIP_DIRECTORY};
 
 use maat_graphics::graphics::CoreRender;
 use maat_graphics::CoreMaat;
 use maat_graphics::DrawCall;
 
-use cgmath::{Vector2, Vector4};
+use acwars::cgmath::{Vector2, Vector4};
+
+use hlua::Lua;
 
+use std::env;
 use std::time;
 
 const MAJOR: u32 = 0;
 const MINOR: u32 = 0;
 const PATCH: u32 = 1;
 
-const DELTA_STEP: f32 = 0.001;
-
 fn benchmark(draw_calls: &mut Vec<DrawCall>, dimensions: Vector2<f32>) {
   draw_calls.push(DrawCall::draw_text_basic(Vector2::new(dimensions.x - 80.0, 15.0), 
                                            Vector2::new(64.0, 64.0), 
@@ -95,11 +92,35 @@ fn main() {
   graphics.create_instance_buffer("Wall".to_string());
   graphics.create_instance_buffer("BlueShield".to_string());
   
+  // Ship definitions that bring their own texture
+  for definition in ShipDefinition::load_all(SHIP_DIRECTORY) {
+    if let Some(texture_path) = definition.texture_path {
+      graphics.add_texture(definition.texture.to_string(), texture_path);
+      graphics.create_instance_buffer(definition.texture.to_string());
+    }
+  }
+  
   graphics.load_shaders();
   
   graphics.set_clear_colour(0.2, 0.2, 0.2, 1.0);
   
-  let mut game: Box<Scene> = Box::new(LoadScreen::new());
+  let mut load_screen = LoadScreen::new();
+  let args: Vec<String> = env::args().collect();
+  if args.len() > 2 && args[1] == "--replay" {
+    match Replay::load(&args[2]) {
+      Ok(replay) => {
+        load_screen = load_screen.with_replay(replay);
+      },
+      Err(e) => {
+        println!("Failed to load replay {}: {}", args[2], e);
+      }
+    }
+  }
+  
+  let mut game: Box<Scene> = Box::new(load_screen);
+  
+  let mut lua = Lua::new();
+  lua.openlibs();
   
   let mut draw_calls: Vec<DrawCall> = Vec::with_capacity(100);
   
@@ -153,7 +174,7 @@ fn main() {
     let delta_steps = (total_delta_time / DELTA_STEP).floor() as usize;
     
     for _ in 0..delta_steps {
-      game.update(None, None, DELTA_STEP);
+      game.update(None, Some(&mut lua), DELTA_STEP);
       total_delta_time -= DELTA_STEP;
     }
     
@@ -15,7 +15,7 @@ pub struct Dash {
 impl Dash {
   pub fn new() -> Dash {
     Dash {
-      data: AbilityData::new_active("DashIcon".to_string(), 5.0),
+      data: AbilityData::new_active("DashIcon".to_string(), 5.0).with_energy_cost(15.0),
     }
   }
 }
@@ -14,7 +14,7 @@ pub struct DoubleShot {
 impl DoubleShot {
   pub fn new() -> DoubleShot {
     DoubleShot {
-      data: AbilityData::new_active("DoubleShotIcon".to_string(), 0.15),
+      data: AbilityData::new_active("DoubleShotIcon".to_string(), 0.15).with_energy_cost(3.0),
     }
   }
 }
@@ -28,6 +28,15 @@ impl Ability for DoubleShot {
     &mut self.data
   }
   
+  fn is_weapon(&self) -> bool {
+    true
+  }
+  
+  fn range(&self) -> f32 {
+    let zero = Vector2::new(0.0, 0.0);
+    self.projectile_range(Box::new(Ftpl::new(zero, zero, Vector2::new(0.0, 1.0))))
+  }
+  
   fn apply_passive_effect(&self, _projectile: &mut BoxProjectile) {
     
   }
@@ -13,7 +13,7 @@ pub struct Haste {
 impl Haste {
   pub fn new() -> Haste {
     Haste {
-      data: AbilityData::new_active("HasteIcon".to_string(), 5.0),
+      data: AbilityData::new_active("HasteIcon".to_string(), 5.0).with_energy_cost(25.0),
     }
   }
 }
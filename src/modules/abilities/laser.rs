@@ -14,7 +14,7 @@ pub struct Laser {
 impl Laser {
   pub fn new() -> Laser {
     Laser {
-      data: AbilityData::new_active("LaserBeamIcon".to_string(), 1.25),
+      data: AbilityData::new_active("LaserBeamIcon".to_string(), 1.25).with_energy_cost(20.0),
     }
   }
 }
@@ -28,6 +28,15 @@ impl Ability for Laser {
     &mut self.data
   }
   
+  fn is_weapon(&self) -> bool {
+    true
+  }
+  
+  fn range(&self) -> f32 {
+    let zero = Vector2::new(0.0, 0.0);
+    self.projectile_range(Box::new(LaserBeam::new(zero, zero, Vector2::new(0.0, 1.0))))
+  }
+  
   fn apply_passive_effect(&self, _projectile: &mut BoxProjectile) {
     
   }
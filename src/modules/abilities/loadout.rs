@@ -0,0 +1,149 @@
+use crate::modules::abilities::BoxAbility;
+use crate::modules::entities::{BoxEntity, Hostility};
+
+use crate::cgmath::Vector2;
+
+// What a controller can fire, any abilities it was given itself followed by the abilities in
+// its ship's slots. Synced with the ship every tick, so installing or removing an ability takes
+// effect straight away, a destroyed section's abilities drop out, and an ability keeps its
+// cooldown for as long as its slot holds it.
+#[derive(Clone)]
+pub struct Loadout {
+  abilities: Vec<BoxAbility>,
+  // slot id and linked slot id of each ability, None for ones given directly
+  slots: Vec<Option<(i32, Option<i32>)>>,
+}
+
+impl Loadout {
+  pub fn new() -> Loadout {
+    Loadout {
+      abilities: Vec::new(),
+      slots: Vec::new(),
+    }
+  }
+  
+  pub fn with_ability(mut self, ability: BoxAbility) -> Loadout {
+    self.abilities.push(ability);
+    self.slots.push(None);
+    self
+  }
+  
+  pub fn len(&self) -> usize {
+    self.abilities.len()
+  }
+  
+  pub fn is_empty(&self) -> bool {
+    self.abilities.is_empty()
+  }
+  
+  pub fn abilities(&self) -> &Vec<BoxAbility> {
+    &self.abilities
+  }
+  
+  pub fn ability(&self, index: usize) -> Option<&BoxAbility> {
+    self.abilities.get(index)
+  }
+  
+  pub fn mut_ability(&mut self, index: usize) -> Option<&mut BoxAbility> {
+    self.abilities.get_mut(index)
+  }
+  
+  // Index of the ability in the slot with slot_id, None if the slot is empty, destroyed or
+  // not on the ship
+  pub fn slot_index(&self, slot_id: i32) -> Option<usize> {
+    self.slots.iter().position(|slot| slot.map(|(id, _)| id) == Some(slot_id))
+  }
+  
+  // Slot id of each ability in order, None for ones given directly
+  pub fn slot_ids(&self) -> Vec<Option<i32>> {
+    self.slots.iter().map(|slot| slot.map(|(id, _)| id)).collect()
+  }
+  
+  // Moves the abilities it was given into the ship's slots in id order, replacing whatever
+  // the ship came with. Any that don't fit stay with the loadout.
+  pub fn refit(&mut self, ship: &mut BoxEntity) {
+    let given: Vec<BoxAbility> = self.abilities.iter().zip(self.slots.iter())
+                                     .filter(|(_, slot)| slot.is_none())
+                                     .map(|(ability, _)| ability.clone())
+                                     .collect();
+    let slots = ship.modular_slots();
+    if given.is_empty() || slots.is_empty() {
+      return;
+    }
+    
+    for slot in &slots {
+      ship.remove_ability(slot.id());
+    }
+    
+    let mut leftover = Loadout::new();
+    for (i, ability) in given.into_iter().enumerate() {
+      match slots.get(i) {
+        Some(slot) => { ship.install_ability(slot.id(), ability); },
+        None => { leftover = leftover.with_ability(ability); },
+      }
+    }
+    
+    *self = leftover;
+    self.sync(ship);
+  }
+  
+  pub fn sync(&mut self, ship: &BoxEntity) {
+    let mut abilities = Vec::new();
+    let mut slots = Vec::new();
+    for i in 0..self.abilities.len() {
+      if self.slots[i].is_none() {
+        abilities.push(self.abilities[i].clone());
+        slots.push(None);
+      }
+    }
+    
+    for slot in ship.ability_slots() {
+      if let Some(ability) = slot.ability() {
+        let existing = self.slot_index(slot.id()).filter(|&i| self.abilities[i].texture() == ability.texture());
+        
+        abilities.push(existing.map(|i| self.abilities[i].clone()).unwrap_or(ability));
+        slots.push(Some((slot.id(), slot.linked_id())));
+      }
+    }
+    
+    self.abilities = abilities;
+    self.slots = slots;
+  }
+  
+  pub fn update(&mut self, delta_time: f32) {
+    for ability in &mut self.abilities {
+      ability.update(delta_time);
+    }
+  }
+  
+  // index and every ability whose slot is linked to it, directly or through other slots
+  pub fn linked(&self, index: usize) -> Vec<usize> {
+    let mut group = vec!(index);
+    let mut i = 0;
+    while i < group.len() {
+      if let Some((id, linked_id)) = self.slots[group[i]] {
+        for j in 0..self.slots.len() {
+          if let Some((other_id, other_linked_id)) = self.slots[j] {
+            if !group.contains(&j) && (linked_id == Some(other_id) || other_linked_id == Some(id)) {
+              group.push(j);
+            }
+          }
+        }
+      }
+      i += 1;
+    }
+    
+    group
+  }
+  
+  // Fires the ability along with everything linked to it
+  pub fn activate(&mut self, index: usize, ship: &mut BoxEntity, target: Vector2<f32>, window_size: Vector2<f32>, hostility: &Hostility) {
+    if index >= self.abilities.len() {
+      return;
+    }
+    
+    for i in self.linked(index) {
+      self.abilities[i].activate(ship, target, window_size, hostility);
+    }
+  }
+}
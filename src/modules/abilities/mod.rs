@@ -8,6 +8,7 @@ pub use self::dash::Dash;
 pub use self::haste::Haste;
 pub use self::sun_damage::SunDamage;
 pub use self::no_ability::NoAbility;
+pub use self::loadout::Loadout;
 
 pub use self::projectile_speed::ProjectileSpeed;
 
@@ -27,16 +28,38 @@ mod shatter;
 // other
 mod movement;
 mod no_ability;
+mod loadout;
 
 use maat_graphics::DrawCall;
 
 use crate::modules::entities::{BoxEntity, Hostility};
 use crate::modules::projectiles::{BoxProjectile};
+use crate::modules::buffs::BoxBuff;
 
-use crate::cgmath::{Vector2, Vector4};
+use crate::cgmath::{Vector2, Vector4, InnerSpace};
 
 pub type BoxAbility = Box<Ability + Send + Sync>;
 
+// Abilities by type name, for levels and other data files.
+pub fn ability_by_name(name: &str) -> Option<BoxAbility> {
+  let ability: BoxAbility = match name {
+    "SingleShot" => Box::new(SingleShot::new()),
+    "DoubleShot" => Box::new(DoubleShot::new()),
+    "Move" => Box::new(Move::new()),
+    "Laser" => Box::new(Laser::new()),
+    "Shatter" => Box::new(Shatter::new()),
+    "Shield" => Box::new(Shield::new()),
+    "Dash" => Box::new(Dash::new()),
+    "Haste" => Box::new(Haste::new()),
+    "SunDamage" => Box::new(SunDamage::new()),
+    "NoAbility" => Box::new(NoAbility::new()),
+    "ProjectileSpeed" => Box::new(ProjectileSpeed::new()),
+    _ => return None,
+  };
+  
+  Some(ability)
+}
+
 #[derive(Clone, PartialEq)]
 pub enum AbilityType {
   Active,
@@ -49,7 +72,10 @@ pub struct AbilityData {
   texture: String,
   timer: f32,
   time_left: f32,
+  energy_cost: f32,
   passives: Vec<BoxAbility>,
+  // put on whatever a projectile carrying this passive hits
+  status_effects: Vec<BoxBuff>,
 }
 
 impl AbilityData {
@@ -59,7 +85,9 @@ impl AbilityData {
       texture,
       timer,
       time_left: 0.0,
+      energy_cost: 0.0,
       passives: Vec::new(),
+      status_effects: Vec::new(),
     }
   }
   
@@ -69,9 +97,22 @@ impl AbilityData {
       texture,
       timer,
       time_left: 0.0,
+      energy_cost: 0.0,
       passives: Vec::new(),
+      status_effects: Vec::new(),
     }
   }
+  
+  // Energy taken from the ship each time it is activated
+  pub fn with_energy_cost(mut self, energy_cost: f32) -> AbilityData {
+    self.energy_cost = energy_cost;
+    self
+  }
+  
+  pub fn with_status_effect(mut self, effect: BoxBuff) -> AbilityData {
+    self.status_effects.push(effect);
+    self
+  }
 }
 
 pub trait AbilityClone {
@@ -105,6 +146,24 @@ pub trait Ability: AbilityClone {
     self.data().texture.to_string()
   }
   
+  fn energy_cost(&self) -> f32 {
+    self.data().energy_cost
+  }
+  
+  // Weapons can't be fired while disarmed
+  fn is_weapon(&self) -> bool {
+    false
+  }
+  
+  fn status_effects(&self) -> &Vec<BoxBuff> {
+    &self.data().status_effects
+  }
+  
+  // Only passives carry status effects, onto whatever their projectile hits
+  fn add_status_effect(&mut self, effect: BoxBuff) {
+    self.mut_data().status_effects.push(effect);
+  }
+  
   fn ability_type(&self) -> &AbilityType {
     &self.data().ability_type
   }
@@ -119,6 +178,18 @@ pub trait Ability: AbilityClone {
     }
   }
   
+  // Distance from the ship's centre this ability can reach, 0.0 for abilities that don't fire
+  // a projectile at the target and so can be used from anywhere.
+  fn range(&self) -> f32 {
+    0.0
+  }
+  
+  // Range of a projectile fired from the origin, after this ability's passives are applied
+  fn projectile_range(&self, mut projectile: BoxProjectile) -> f32 {
+    self.apply_passive_abilities(&mut projectile);
+    projectile.position().magnitude() + projectile.range()
+  }
+  
   fn add_passive(&mut self, passive: BoxAbility) {
     if passive.ability_type() == &AbilityType::Passive {
       self.mut_data().passives.push(passive);
@@ -126,7 +197,11 @@ pub trait Ability: AbilityClone {
   }
   
   fn activate(&mut self, ship: &mut BoxEntity, target: Vector2<f32>, window_size: Vector2<f32>, parent_hostility: &Hostility) {
-    if self.can_activate() {
+    if ship.is_stunned() || (self.is_weapon() && ship.is_disarmed()) {
+      return;
+    }
+    
+    if self.can_activate() && ship.use_energy(self.energy_cost()) {
       self.applied_to(ship, target, window_size, parent_hostility);
       self.mut_data().time_left = self.data().timer;
     }
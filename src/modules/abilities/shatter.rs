@@ -3,6 +3,7 @@ use maat_graphics::math;
 use crate::modules::abilities::{Ability, AbilityData};
 use crate::modules::entities::{BoxEntity, Hostility};
 use crate::modules::projectiles::{Projectile, BoxProjectile, Ftpl};
+use crate::modules::buffs::BoxBuff;
 
 use crate::cgmath::{Vector2};
 
@@ -17,6 +18,12 @@ impl Shatter {
       data: AbilityData::new_passive("ShatterIcon".to_string(), 0.0),
     }
   }
+  
+  // Also puts effect on whatever the projectile hits
+  pub fn with_status_effect(mut self, effect: BoxBuff) -> Shatter {
+    self.data = self.data.with_status_effect(effect);
+    self
+  }
 }
 
 impl Ability for Shatter {
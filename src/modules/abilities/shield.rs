@@ -12,7 +12,7 @@ pub struct Shield {
 impl Shield {
   pub fn new() -> Shield {
     Shield {
-      data: AbilityData::new_active("ShieldIcon".to_string(), 5.0),
+      data: AbilityData::new_active("ShieldIcon".to_string(), 5.0).with_energy_cost(30.0),
     }
   }
 }
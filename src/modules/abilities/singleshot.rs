@@ -14,7 +14,7 @@ pub struct SingleShot {
 impl SingleShot {
   pub fn new() -> SingleShot {
     SingleShot {
-      data: AbilityData::new_active("SingleShotIcon".to_string(), 0.75),
+      data: AbilityData::new_active("SingleShotIcon".to_string(), 0.75).with_energy_cost(5.0),
     }
   }
 }
@@ -28,6 +28,15 @@ impl Ability for SingleShot {
     &mut self.data
   }
   
+  fn is_weapon(&self) -> bool {
+    true
+  }
+  
+  fn range(&self) -> f32 {
+    let zero = Vector2::new(0.0, 0.0);
+    self.projectile_range(Box::new(Gob::new(zero, zero, Vector2::new(0.0, 1.0))))
+  }
+  
   fn apply_passive_effect(&self, _projectile: &mut BoxProjectile) {
     
   }
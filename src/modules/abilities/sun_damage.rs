@@ -28,6 +28,10 @@ impl Ability for SunDamage {
     &mut self.data
   }
   
+  fn is_weapon(&self) -> bool {
+    true
+  }
+  
   fn apply_passive_effect(&self, _projectile: &mut BoxProjectile) {
     
   }
@@ -5,6 +5,8 @@ use crate::modules::abilities::{SingleShot};
 
 use crate::cgmath::Vector2;
 
+use hlua::Lua;
+
 #[derive(Clone)]
 pub struct BenchmarkArea {
   data: AreaData,
@@ -39,7 +41,7 @@ impl Area for BenchmarkArea {
     &mut self.data
   }
   
-  fn update_area(&mut self, _delta_time: f32) {
+  fn update_area(&mut self, _lua: Option<&mut Lua>, _delta_time: f32) {
     
   }
 }
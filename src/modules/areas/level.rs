@@ -0,0 +1,333 @@
+use crate::modules::areas::{Area, AreaData, BoxArea};
+use crate::modules::entities::{FullEntity, BoxEntity, Hostility, Sun, Wall, Astroid, ShipDefinition, SHIP_DIRECTORY};
+use crate::modules::controllers::{BoxEntityController, IdleAi, FloatingAi, AbilitySpamAi, StateMachineAi, WingmanAi, LuaController};
+use crate::modules::scripting::SCRIPT_DIRECTORY;
+use crate::modules::abilities::{self, BoxAbility};
+use crate::modules::buffs::{self, BoxBuff};
+
+use crate::cgmath::Vector2;
+
+use hlua::Lua;
+
+use serde::Deserialize;
+
+use std::fs;
+use std::path::Path;
+
+pub const LEVEL_DIRECTORY: &str = "./resources/levels";
+pub const DEFAULT_LEVEL: &str = "default";
+
+#[derive(Clone, Deserialize)]
+pub enum EntityKind {
+  Sun,
+  Wall,
+  // The ships in brew.ron and bulbz.ron
+  Brew,
+  Ship,
+  Astroid,
+  // A ship from resources/ships by name
+  Defined(String),
+}
+
+impl EntityKind {
+  pub fn from_name(name: &str) -> EntityKind {
+    match name {
+      "Sun" => EntityKind::Sun,
+      "Wall" => EntityKind::Wall,
+      "Brew" => EntityKind::Brew,
+      "Ship" => EntityKind::Ship,
+      "Astroid" => EntityKind::Astroid,
+      _ => EntityKind::Defined(name.to_string()),
+    }
+  }
+}
+
+#[derive(Clone, Deserialize)]
+pub enum ControllerKind {
+  Idle,
+  Floating,
+  AbilitySpam,
+  StateMachine,
+  // A WingmanAi holding this offset from the player, y is the way the player faces
+  Wingman((f32, f32)),
+  // A LuaController running a script from resources/scripts
+  Lua(String),
+}
+
+impl ControllerKind {
+  pub fn from_name(name: &str) -> Option<ControllerKind> {
+    match name {
+      "Idle" => Some(ControllerKind::Idle),
+      "Floating" => Some(ControllerKind::Floating),
+      "AbilitySpam" => Some(ControllerKind::AbilitySpam),
+      "StateMachine" => Some(ControllerKind::StateMachine),
+      "Wingman" => Some(ControllerKind::Wingman((0.0, -200.0))),
+      _ if name.ends_with(".lua") => Some(ControllerKind::Lua(name.to_string())),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct AbilityDefinition {
+  pub name: String,
+  #[serde(default)]
+  pub passives: Vec<String>,
+  // buffs or status effects by name, carried by the passives onto whatever they hit
+  #[serde(default)]
+  pub status_effects: Vec<String>,
+}
+
+// Repeats an entity over a grid of columns by rows, starting at the entity's position.
+#[derive(Clone, Deserialize)]
+pub struct GridDefinition {
+  pub columns: u32,
+  pub rows: u32,
+  pub spacing: (f32, f32),
+}
+
+#[derive(Clone, Deserialize)]
+pub struct EntityDefinition {
+  pub kind: EntityKind,
+  // relative to the centre of the area
+  pub position: (f32, f32),
+  // only used by Astroid
+  #[serde(default)]
+  pub size: Option<(f32, f32)>,
+  pub hostility: Hostility,
+  pub controller: ControllerKind,
+  #[serde(default)]
+  pub abilities: Vec<AbilityDefinition>,
+  #[serde(default)]
+  pub grid: Option<GridDefinition>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct AreaDefinition {
+  pub position: (f32, f32),
+  pub size: (f32, f32),
+  #[serde(default)]
+  pub entities: Vec<EntityDefinition>,
+  // set false to stop Suns and other wells in the area pulling anything
+  #[serde(default = "default_gravity")]
+  pub gravity: bool,
+}
+
+fn default_gravity() -> bool {
+  true
+}
+
+// A level file from resources/levels, see default.ron for the layout.
+#[derive(Clone, Deserialize)]
+pub struct Level {
+  pub name: String,
+  // file in resources/scripts run when the battle starts
+  #[serde(default)]
+  pub script: Option<String>,
+  pub areas: Vec<AreaDefinition>,
+  // entities placed relative to the player's ship, for friendly wingmen
+  #[serde(default)]
+  pub escort: Vec<EntityDefinition>,
+}
+
+impl Level {
+  pub fn load<P: AsRef<Path>>(path: P) -> Result<Level, String> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    
+    ron::de::from_str(&contents).map_err(|e| format!("{}: {}", path.display(), e))
+  }
+  
+  pub fn load_named(name: &str) -> Result<Level, String> {
+    Level::load(Path::new(LEVEL_DIRECTORY).join(name.to_string() + ".ron"))
+  }
+  
+  pub fn areas(&self) -> Result<Vec<BoxArea>, String> {
+    let ships = ShipDefinition::load_all(SHIP_DIRECTORY);
+    
+    let mut areas: Vec<BoxArea> = Vec::new();
+    for area in &self.areas {
+      areas.push(Box::new(LevelArea::new(area, &ships)?));
+    }
+    
+    Ok(areas)
+  }
+  
+  // An area around the player's starting position holding the escort, None without one
+  pub fn escort_area(&self, player_position: Vector2<f32>) -> Result<Option<BoxArea>, String> {
+    if self.escort.is_empty() {
+      return Ok(None);
+    }
+    
+    let ships = ShipDefinition::load_all(SHIP_DIRECTORY);
+    let definition = AreaDefinition {
+      position: (player_position.x, player_position.y),
+      size: (0.0, 0.0),
+      entities: self.escort.clone(),
+      gravity: true,
+    };
+    
+    Ok(Some(Box::new(LevelArea::new(&definition, &ships)?)))
+  }
+}
+
+impl AbilityDefinition {
+  pub fn to_ability(&self) -> Result<BoxAbility, String> {
+    let mut ability = abilities::ability_by_name(&self.name).ok_or(format!("unknown ability {}", self.name))?;
+    
+    let mut effects: Vec<BoxBuff> = Vec::new();
+    for effect in &self.status_effects {
+      effects.push(buffs::buff_by_name(effect).ok_or(format!("unknown status effect {}", effect))?);
+    }
+    if !effects.is_empty() && self.passives.is_empty() {
+      return Err(format!("{} has status effects but no passive to carry them", self.name));
+    }
+    
+    for passive in &self.passives {
+      let mut passive = abilities::ability_by_name(passive).ok_or(format!("unknown passive {}", passive))?;
+      for effect in &effects {
+        passive.add_status_effect(effect.clone());
+      }
+      ability.add_passive(passive);
+    }
+    
+    Ok(ability)
+  }
+}
+
+impl EntityDefinition {
+  fn to_entity(&self, position: Vector2<f32>, ships: &Vec<ShipDefinition>) -> Result<BoxEntity, String> {
+    let defined = |name: &str| -> Result<BoxEntity, String> {
+      let definition = ships.iter().find(|ship| ship.name == name).ok_or(format!("unknown ship {}", name))?;
+      Ok(definition.to_entity(position))
+    };
+    
+    let mut entity: BoxEntity = match &self.kind {
+      EntityKind::Sun => Box::new(Sun::new(position)),
+      EntityKind::Wall => Box::new(Wall::new(position)),
+      EntityKind::Brew => defined("Brew")?,
+      EntityKind::Ship => defined("Bulbz")?,
+      EntityKind::Astroid => {
+        let size = self.size.unwrap_or((100.0, 100.0));
+        Box::new(Astroid::new(position, Vector2::new(size.0, size.1)))
+      },
+      EntityKind::Defined(name) => defined(name)?,
+    };
+    
+    entity.set_hostility(self.hostility.clone());
+    
+    Ok(entity)
+  }
+  
+  fn to_controller(&self) -> Result<BoxEntityController, String> {
+    let mut abilities = Vec::new();
+    for ability in &self.abilities {
+      abilities.push(ability.to_ability()?);
+    }
+    
+    let controller: BoxEntityController = match &self.controller {
+      ControllerKind::Idle => {
+        let mut ai = IdleAi::new();
+        for ability in abilities {
+          ai = ai.with_ability(ability);
+        }
+        Box::new(ai)
+      },
+      ControllerKind::Floating => {
+        let mut ai = FloatingAi::new();
+        for ability in abilities {
+          ai = ai.with_ability(ability);
+        }
+        Box::new(ai)
+      },
+      ControllerKind::AbilitySpam => {
+        let mut ai = AbilitySpamAi::new();
+        for ability in abilities {
+          ai = ai.with_ability(ability);
+        }
+        Box::new(ai)
+      },
+      ControllerKind::StateMachine => {
+        let mut ai = StateMachineAi::new();
+        for ability in abilities {
+          ai = ai.with_ability(ability);
+        }
+        Box::new(ai)
+      },
+      ControllerKind::Wingman(offset) => {
+        let mut ai = WingmanAi::new(Vector2::new(offset.0, offset.1));
+        for ability in abilities {
+          ai = ai.with_ability(ability);
+        }
+        Box::new(ai)
+      },
+      ControllerKind::Lua(script) => {
+        let mut ai = LuaController::load(Path::new(SCRIPT_DIRECTORY).join(script))?;
+        for ability in abilities {
+          ai = ai.with_ability(ability);
+        }
+        Box::new(ai)
+      },
+    };
+    
+    Ok(controller)
+  }
+  
+  pub fn to_full_entities(&self, area_position: Vector2<f32>, ships: &Vec<ShipDefinition>) -> Result<Vec<FullEntity>, String> {
+    let origin = area_position + Vector2::new(self.position.0, self.position.1);
+    
+    let (columns, rows, spacing) = match &self.grid {
+      Some(grid) => (grid.columns, grid.rows, Vector2::new(grid.spacing.0, grid.spacing.1)),
+      None => (1, 1, Vector2::new(0.0, 0.0)),
+    };
+    
+    let mut full_entities = Vec::new();
+    for i in 0..columns {
+      for j in 0..rows {
+        let position = origin + Vector2::new(spacing.x*i as f32, spacing.y*j as f32);
+        full_entities.push(FullEntity::new(self.to_controller()?, self.to_entity(position, ships)?));
+      }
+    }
+    
+    Ok(full_entities)
+  }
+}
+
+#[derive(Clone)]
+pub struct LevelArea {
+  data: AreaData,
+}
+
+impl LevelArea {
+  pub fn new(definition: &AreaDefinition, ships: &Vec<ShipDefinition>) -> Result<LevelArea, String> {
+    let position = Vector2::new(definition.position.0, definition.position.1);
+    let size = Vector2::new(definition.size.0, definition.size.1);
+    
+    let mut data = AreaData::new(position, size).with_gravity(definition.gravity);
+    for entity in &definition.entities {
+      for full_entity in entity.to_full_entities(position, ships)? {
+        data = data.with_entity(full_entity);
+      }
+    }
+    
+    Ok(LevelArea {
+      data,
+    })
+  }
+}
+
+impl Area for LevelArea {
+  fn data(&self) -> &AreaData {
+    &self.data
+  }
+  
+  fn mut_data(&mut self) -> &mut AreaData {
+    &mut self.data
+  }
+  
+  fn update_area(&mut self, lua: Option<&mut Lua>, delta_time: f32) {
+    if let Some(lua) = lua {
+      self.run_callbacks(lua, delta_time);
+    }
+  }
+}
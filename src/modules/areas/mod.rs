@@ -1,17 +1,21 @@
-pub use self::solar_system::SolarSystem;
-pub use self::astroid_field::AstroidField;
 pub use self::benchmark::BenchmarkArea;
+pub use self::level::{Level, LevelArea, AreaDefinition, EntityDefinition, EntityKind, ControllerKind,
+                      AbilityDefinition, GridDefinition, LEVEL_DIRECTORY, DEFAULT_LEVEL};
 
-mod solar_system;
-mod astroid_field;
 mod benchmark;
+mod level;
 
 use maat_graphics::DrawCall;
 use crate::modules::projectiles::{BoxProjectile};
-use crate::modules::entities::{MutexEntity, FullEntity, BoxEntity};
+use crate::modules::entities::{MutexEntity, FullEntity};
+use crate::modules::controllers::TargetView;
+use crate::modules::scripting::ScriptApi;
+use crate::modules::buffs;
 
 use crate::cgmath::Vector2;
 
+use hlua::{Lua, LuaFunction};
+
 use std::sync::Arc;
 
 pub type BoxArea = Box<Area>;
@@ -21,7 +25,10 @@ pub struct AreaData {
   position: Vector2<f32>,
   size: Vector2<f32>,
   entities: Vec<FullEntity>,
-  // events
+  // lua globals called every tick
+  callbacks: Vec<String>,
+  // whether wells carried by its entities pull anything
+  gravity: bool,
 }
 
 impl AreaData {
@@ -30,9 +37,16 @@ impl AreaData {
       position,
       size,
       entities: Vec::new(),
+      callbacks: Vec::new(),
+      gravity: true,
     }
   }
   
+  pub fn with_gravity(mut self, gravity: bool) -> AreaData {
+    self.gravity = gravity;
+    self
+  }
+  
   pub fn with_entity(mut self, entity: FullEntity) -> AreaData {
     self.entities.push(entity);
     self
@@ -59,7 +73,58 @@ pub trait Area: AreaClone {
   fn data(&self) -> &AreaData;
   fn mut_data(&mut self) -> &mut AreaData;
   
-  fn update_area(&mut self, delta_time: f32);
+  fn update_area(&mut self, lua: Option<&mut Lua>, delta_time: f32);
+  
+  fn position(&self) -> Vector2<f32> {
+    self.data().position
+  }
+  
+  fn size(&self) -> Vector2<f32> {
+    self.data().size
+  }
+  
+  fn has_gravity(&self) -> bool {
+    self.data().gravity
+  }
+  
+  fn set_gravity(&mut self, gravity: bool) {
+    self.mut_data().gravity = gravity;
+  }
+  
+  fn add_entity(&mut self, entity: FullEntity) {
+    self.mut_data().entities.push(entity);
+  }
+  
+  fn add_callback(&mut self, function: String) {
+    self.mut_data().callbacks.push(function);
+  }
+  
+  fn full_entity(&mut self, id: u32) -> Option<&mut FullEntity> {
+    self.mut_data().entities.iter_mut().find(|full_entity| full_entity.entity.lock().id() == id)
+  }
+  
+  fn attach_scripting(&mut self, scripting: &ScriptApi) {
+    for full_entity in &mut self.mut_data().entities {
+      full_entity.ai.attach_scripting(scripting);
+    }
+  }
+  
+  fn run_callbacks(&self, lua: &mut Lua, delta_time: f32) {
+    for callback in &self.data().callbacks {
+      let function: Option<LuaFunction<_>> = lua.get(callback.as_str());
+      match function {
+        Some(mut function) => {
+          let result: Result<(), _> = function.call_with_args(delta_time);
+          if let Err(e) = result {
+            println!("Lua area callback {} failed: {:?}", callback, e);
+          }
+        },
+        None => {
+          println!("Lua area callback {} is not a function", callback);
+        }
+      }
+    }
+  }
   
   fn entities(&self) -> Vec<MutexEntity> {
     let mut arc_entities = Vec::new();
@@ -103,31 +168,21 @@ pub trait Area: AreaClone {
     }
   }*/
   
-  fn update(&mut self, ship: &mut BoxEntity, window_size: Vector2<f32>, delta_time: f32) -> Vec<BoxProjectile> {
-    self.update_area(delta_time);
+  fn update(&mut self, targets: &TargetView, window_size: Vector2<f32>, lua: Option<&mut Lua>, delta_time: f32) -> Vec<BoxProjectile> {
+    self.update_area(lua, delta_time);
     
     let mut new_projectiles: Vec<BoxProjectile> = Vec::new();
     
     // entities
-    let ship_pos = ship.position();
     let area_pos = self.data().position;
     let area_size = self.data().size;
     for object in &mut self.mut_data().entities {
       let mut entity = object.entity.lock();
-      object.ai.update(&mut *entity, ship_pos, area_pos, area_size, window_size, delta_time);
+      object.ai.sync_abilities(&*entity);
+      let target = object.ai.select_target(&*entity, targets);
+      object.ai.update(&mut *entity, target, area_pos, area_size, window_size, delta_time);
       
-      let mut offset = 0;
-      for i in 0..object.buffs.len() {
-        if offset > i {
-          break;
-        }
-        object.buffs[i-offset].update(&mut *entity, delta_time);
-        if !object.buffs[i-offset].should_exist() {
-          object.buffs[i-offset].unapply_buff(&mut *entity);
-          object.buffs.remove(i-offset);
-          offset += 1;
-        }
-      }
+      buffs::update_buffs(&mut object.buffs, &mut *entity, delta_time);
     }
     
     let mut offset = 0;
@@ -150,12 +205,12 @@ pub trait Area: AreaClone {
         }
       }
       
-      for buff in object_buffs {
-        {
-          let mut entity = self.data().entities[i-offset].entity.lock();
-          buff.apply_buff(&mut *entity);
+      {
+        let object = &mut self.mut_data().entities[i-offset];
+        let mut entity = object.entity.lock();
+        for buff in object_buffs {
+          buffs::add_buff(&mut object.buffs, buff, &mut *entity);
         }
-        self.mut_data().entities[i-offset].buffs.push(buff);
       }
       
       if !should_exist {
@@ -0,0 +1,211 @@
+use crate::modules::entities::MutexEntity;
+use crate::modules::spatial_hash::{SpatialHash, SpatialObject};
+use crate::modules::kdtree::KdTree;
+
+use crate::cgmath::Vector2;
+
+use std::sync::Arc;
+
+pub type BoxBroadphase = Box<Broadphase + Send>;
+
+// Cell size the spatial hash backend uses for world collisions
+pub const CELL_SIZE: f32 = 30.0;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BroadphaseKind {
+  BruteForce,
+  SpatialHash,
+  KdTree,
+}
+
+impl BroadphaseKind {
+  pub fn all() -> Vec<BroadphaseKind> {
+    vec!(BroadphaseKind::BruteForce, BroadphaseKind::SpatialHash, BroadphaseKind::KdTree)
+  }
+  
+  pub fn from_name(name: &str) -> Option<BroadphaseKind> {
+    match name {
+      "brute-force" => Some(BroadphaseKind::BruteForce),
+      "spatial-hash" => Some(BroadphaseKind::SpatialHash),
+      "kd-tree" => Some(BroadphaseKind::KdTree),
+      _ => None,
+    }
+  }
+  
+  pub fn name(&self) -> &'static str {
+    match self {
+      BroadphaseKind::BruteForce => "brute-force",
+      BroadphaseKind::SpatialHash => "spatial-hash",
+      BroadphaseKind::KdTree => "kd-tree",
+    }
+  }
+  
+  pub fn create(&self) -> BoxBroadphase {
+    match self {
+      BroadphaseKind::BruteForce => Box::new(BruteForce::new()),
+      BroadphaseKind::SpatialHash => Box::new(SpatialHashBroadphase::new(CELL_SIZE)),
+      BroadphaseKind::KdTree => Box::new(KdTreeBroadphase::new()),
+    }
+  }
+}
+
+fn overlaps(min_a: Vector2<f32>, max_a: Vector2<f32>, min_b: Vector2<f32>, max_b: Vector2<f32>) -> bool {
+  min_a.x <= max_b.x && max_a.x >= min_b.x && min_a.y <= max_b.y && max_a.y >= min_b.y
+}
+
+// Narrows down which entities a collision pass has to test against each other and against
+// projectiles. Every backend may hand back more than actually touches, but never less.
+pub trait Broadphase {
+  fn kind(&self) -> BroadphaseKind;
+  
+  // Replaces whatever the last build held with these entities
+  fn build(&mut self, entities: &Vec<MutexEntity>);
+  
+  // Lets go of the entities from the last build
+  fn clear(&mut self);
+  
+  // Entities that might overlap the box, each once
+  fn query(&self, min: Vector2<f32>, max: Vector2<f32>) -> Vec<MutexEntity>;
+  
+  // Pairs of entities that might be touching, each once
+  fn pairs(&self) -> Vec<(MutexEntity, MutexEntity)>;
+}
+
+// Checks every entity's bounds against everything, the reference the others are held to
+pub struct BruteForce {
+  entities: Vec<(MutexEntity, Vector2<f32>, Vector2<f32>)>,
+}
+
+impl BruteForce {
+  pub fn new() -> BruteForce {
+    BruteForce {
+      entities: Vec::new(),
+    }
+  }
+}
+
+impl Broadphase for BruteForce {
+  fn kind(&self) -> BroadphaseKind {
+    BroadphaseKind::BruteForce
+  }
+  
+  fn build(&mut self, entities: &Vec<MutexEntity>) {
+    self.entities.clear();
+    for entity in entities {
+      let (min, max) = entity.bounds();
+      self.entities.push((Arc::clone(entity), min, max));
+    }
+  }
+  
+  fn clear(&mut self) {
+    self.entities.clear();
+  }
+  
+  fn query(&self, min: Vector2<f32>, max: Vector2<f32>) -> Vec<MutexEntity> {
+    self.entities.iter()
+                 .filter(|(_, entity_min, entity_max)| overlaps(*entity_min, *entity_max, min, max))
+                 .map(|(entity, _, _)| Arc::clone(entity))
+                 .collect()
+  }
+  
+  fn pairs(&self) -> Vec<(MutexEntity, MutexEntity)> {
+    let mut pairs = Vec::new();
+    for i in 0..self.entities.len() {
+      for j in i+1..self.entities.len() {
+        let (entity_i, min_i, max_i) = &self.entities[i];
+        let (entity_j, min_j, max_j) = &self.entities[j];
+        if overlaps(*min_i, *max_i, *min_j, *max_j) {
+          pairs.push((Arc::clone(entity_i), Arc::clone(entity_j)));
+        }
+      }
+    }
+    
+    pairs
+  }
+}
+
+pub struct SpatialHashBroadphase {
+  spatial_hash: SpatialHash<MutexEntity>,
+}
+
+impl SpatialHashBroadphase {
+  pub fn new(cell_size: f32) -> SpatialHashBroadphase {
+    SpatialHashBroadphase {
+      spatial_hash: SpatialHash::new(cell_size),
+    }
+  }
+}
+
+impl Broadphase for SpatialHashBroadphase {
+  fn kind(&self) -> BroadphaseKind {
+    BroadphaseKind::SpatialHash
+  }
+  
+  fn build(&mut self, entities: &Vec<MutexEntity>) {
+    self.spatial_hash.clear();
+    for entity in entities {
+      self.spatial_hash.insert_object_for_point(Arc::clone(entity));
+    }
+  }
+  
+  fn clear(&mut self) {
+    self.spatial_hash.clear();
+  }
+  
+  fn query(&self, min: Vector2<f32>, max: Vector2<f32>) -> Vec<MutexEntity> {
+    self.spatial_hash.retrieve(min, max)
+  }
+  
+  fn pairs(&self) -> Vec<(MutexEntity, MutexEntity)> {
+    self.spatial_hash.retrieve_possible_collisions()
+  }
+}
+
+pub struct KdTreeBroadphase {
+  tree: KdTree<MutexEntity>,
+}
+
+impl KdTreeBroadphase {
+  pub fn new() -> KdTreeBroadphase {
+    KdTreeBroadphase {
+      tree: KdTree::new(),
+    }
+  }
+}
+
+impl Broadphase for KdTreeBroadphase {
+  fn kind(&self) -> BroadphaseKind {
+    BroadphaseKind::KdTree
+  }
+  
+  fn build(&mut self, entities: &Vec<MutexEntity>) {
+    self.tree.clear();
+    for entity in entities {
+      let (min, max) = entity.bounds();
+      self.tree.insert(Arc::clone(entity), min, max);
+    }
+    self.tree.build();
+  }
+  
+  fn clear(&mut self) {
+    self.tree.clear();
+  }
+  
+  fn query(&self, min: Vector2<f32>, max: Vector2<f32>) -> Vec<MutexEntity> {
+    self.tree.query(min, max).into_iter().map(|index| Arc::clone(self.tree.object(index))).collect()
+  }
+  
+  fn pairs(&self) -> Vec<(MutexEntity, MutexEntity)> {
+    let mut pairs = Vec::new();
+    for i in 0..self.tree.len() {
+      let (min, max) = self.tree.bounds(i);
+      for j in self.tree.query(min, max) {
+        if j > i {
+          pairs.push((Arc::clone(self.tree.object(i)), Arc::clone(self.tree.object(j))));
+        }
+      }
+    }
+    
+    pairs
+  }
+}
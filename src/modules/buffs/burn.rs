@@ -0,0 +1,64 @@
+use crate::modules::buffs::{Buff, BuffData, StatusKind, StackRule};
+use crate::modules::damage::{Damage, DamageType};
+
+use crate::modules::entities::{BoxEntity};
+
+#[derive(Clone)]
+pub struct Burn {
+  data: BuffData,
+  // thermal damage each tick, for every stack
+  damage: f32,
+}
+
+impl Burn {
+  pub fn new() -> Burn {
+    Burn {
+      data: BuffData::new()
+                      .with_timer(3.0)
+                      .with_tick_interval(0.5)
+                      .with_stack_rule(StackRule::Intensity)
+                      .with_max_stacks(5),
+      damage: 0.5,
+    }
+  }
+  
+  pub fn with_damage(mut self, damage: f32) -> Burn {
+    self.damage = damage;
+    self
+  }
+  
+  pub fn with_timer(mut self, timer: f32) -> Burn {
+    self.data = self.data.with_timer(timer);
+    self
+  }
+}
+
+impl Buff for Burn {
+  fn data(&self) -> &BuffData {
+    &self.data
+  }
+  
+  fn mut_data(&mut self) -> &mut BuffData {
+    &mut self.data
+  }
+  
+  fn kind(&self) -> StatusKind {
+    StatusKind::Burn
+  }
+  
+  fn reapply_buff(&self, _entity: &mut BoxEntity) {
+  
+  }
+  
+  fn apply_buff(&self, _entity: &mut BoxEntity) {
+  
+  }
+  
+  fn unapply_buff(&self, _entity: &mut BoxEntity) {
+  
+  }
+  
+  fn tick(&self, entity: &mut BoxEntity) {
+    entity.hit(Damage::new(self.damage*self.stacks() as f32, DamageType::Thermal));
+  }
+}
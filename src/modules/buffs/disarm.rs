@@ -0,0 +1,50 @@
+use crate::modules::buffs::{Buff, BuffData, StatusKind, StackRule};
+
+use crate::modules::entities::{BoxEntity};
+
+// Leaves the entity unable to fire its weapons
+#[derive(Clone)]
+pub struct Disarm {
+  data: BuffData,
+}
+
+impl Disarm {
+  pub fn new() -> Disarm {
+    Disarm {
+      data: BuffData::new()
+                      .with_timer(3.0)
+                      .with_stack_rule(StackRule::Refresh),
+    }
+  }
+  
+  pub fn with_timer(mut self, timer: f32) -> Disarm {
+    self.data = self.data.with_timer(timer);
+    self
+  }
+}
+
+impl Buff for Disarm {
+  fn data(&self) -> &BuffData {
+    &self.data
+  }
+  
+  fn mut_data(&mut self) -> &mut BuffData {
+    &mut self.data
+  }
+  
+  fn kind(&self) -> StatusKind {
+    StatusKind::Disarm
+  }
+  
+  fn reapply_buff(&self, _entity: &mut BoxEntity) {
+  
+  }
+  
+  fn apply_buff(&self, entity: &mut BoxEntity) {
+    entity.set_disarmed(true);
+  }
+  
+  fn unapply_buff(&self, entity: &mut BoxEntity) {
+    entity.set_disarmed(false);
+  }
+}
@@ -1,6 +1,7 @@
-use crate::modules::buffs::{Buff, BuffData};
+use crate::modules::buffs::{Buff, BuffData, StatusKind};
 
 use crate::modules::entities::{BoxEntity};
+use crate::modules::stats::{Stat, StatModifier};
 
 #[derive(Clone)]
 pub struct MaxSpeedBuff {
@@ -36,17 +37,23 @@ impl Buff for MaxSpeedBuff {
     &mut self.data
   }
   
+  fn kind(&self) -> StatusKind {
+    StatusKind::MaxSpeed
+  }
+  
   fn reapply_buff(&self, _entity: &mut BoxEntity) {
     
   }
   
-  fn apply_buff(&self, entity: &mut BoxEntity) {
-    let max_vel = entity.max_velocity();
-    entity.set_max_velocty(max_vel * self.data().multiplier);
+  fn apply_buff(&self, _entity: &mut BoxEntity) {
+    
+  }
+  
+  fn unapply_buff(&self, _entity: &mut BoxEntity) {
+    
   }
   
-  fn unapply_buff(&self, entity: &mut BoxEntity) {
-    let max_vel = entity.max_velocity();
-    entity.set_max_velocty(max_vel / self.data().multiplier);
+  fn modifiers(&self) -> Vec<StatModifier> {
+    vec!(StatModifier::multiply(Stat::MaxVelocity, self.data().multiplier))
   }
 }
@@ -1,20 +1,99 @@
 pub use self::max_speed_buff::MaxSpeedBuff;
 pub use self::speed_buff::SpeedBuff;
 pub use self::phase_buff::PhaseBuff;
+pub use self::burn::Burn;
+pub use self::slow::Slow;
+pub use self::stun::Stun;
+pub use self::disarm::Disarm;
+pub use self::regen::Regen;
 
 mod max_speed_buff;
 mod speed_buff;
 mod phase_buff;
 
+// status effects
+mod burn;
+mod slow;
+mod stun;
+mod disarm;
+mod regen;
+
 use crate::modules::entities::{BoxEntity};
+use crate::modules::stats::StatModifier;
+
+use crate::cgmath::Vector4;
+
+pub type BoxBuff = Box<Buff + Send + Sync>;
+
+// Buffs by type name, for scripts and other data files.
+pub fn buff_by_name(name: &str) -> Option<BoxBuff> {
+  let buff: BoxBuff = match name {
+    "MaxSpeedBuff" => Box::new(MaxSpeedBuff::new()),
+    "SpeedBuff" => Box::new(SpeedBuff::new()),
+    "PhaseBuff" => Box::new(PhaseBuff::new()),
+    "Burn" => Box::new(Burn::new()),
+    "Slow" => Box::new(Slow::new()),
+    "Stun" => Box::new(Stun::new()),
+    "Disarm" => Box::new(Disarm::new()),
+    "Regen" => Box::new(Regen::new()),
+    _ => return None,
+  };
+  
+  Some(buff)
+}
+
+// What a buff does, so only one of each kind is ever active on an entity and entities can be
+// immune to some kinds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusKind {
+  MaxSpeed,
+  Speed,
+  Phase,
+  Burn,
+  Slow,
+  Stun,
+  Disarm,
+  Regen,
+}
 
-pub type BoxBuff = Box<Buff>;
+impl StatusKind {
+  // Shown under the health bar
+  pub fn colour(&self) -> Vector4<f32> {
+    match self {
+      StatusKind::MaxSpeed => Vector4::new(0.0, 1.0, 1.0, 0.8),
+      StatusKind::Speed => Vector4::new(1.0, 1.0, 1.0, 0.8),
+      StatusKind::Phase => Vector4::new(0.6, 0.0, 1.0, 0.8),
+      StatusKind::Burn => Vector4::new(1.0, 0.4, 0.0, 0.8),
+      StatusKind::Slow => Vector4::new(0.3, 0.5, 1.0, 0.8),
+      StatusKind::Stun => Vector4::new(1.0, 1.0, 0.0, 0.8),
+      StatusKind::Disarm => Vector4::new(0.5, 0.5, 0.5, 0.8),
+      StatusKind::Regen => Vector4::new(0.0, 1.0, 0.3, 0.8),
+    }
+  }
+}
+
+// What happens when a buff lands on an entity that already has one of the same kind
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StackRule {
+  // the new one replaces the old one, starting its timer over
+  Refresh,
+  // the old one gains a stack, up to its max, and its timer starts over
+  Intensity,
+  // the new one is ignored
+  Unique,
+}
 
 #[derive(Clone)]
 pub struct BuffData {
   multiplier: f32,
   timer: f32,
   should_exist: bool,
+  stack_rule: StackRule,
+  stacks: u32,
+  max_stacks: u32,
+  // seconds between ticks, 0 for buffs that don't tick
+  tick_interval: f32,
+  tick_timer: f32,
 }
 
 impl BuffData {
@@ -23,6 +102,11 @@ impl BuffData {
       multiplier: 1.0,
       timer: 5.0,
       should_exist: true,
+      stack_rule: StackRule::Refresh,
+      stacks: 1,
+      max_stacks: 1,
+      tick_interval: 0.0,
+      tick_timer: 0.0,
     }
   }
   
@@ -35,20 +119,35 @@ impl BuffData {
     self.timer = timer;
     self
   }
+  
+  pub fn with_stack_rule(mut self, stack_rule: StackRule) -> BuffData {
+    self.stack_rule = stack_rule;
+    self
+  }
+  
+  pub fn with_max_stacks(mut self, max_stacks: u32) -> BuffData {
+    self.max_stacks = max_stacks;
+    self
+  }
+  
+  pub fn with_tick_interval(mut self, tick_interval: f32) -> BuffData {
+    self.tick_interval = tick_interval;
+    self
+  }
 }
 
 pub trait BuffClone {
-  fn clone_buff(&self) -> Box<Buff>;
+  fn clone_buff(&self) -> BoxBuff;
 }
 
-impl<T: 'static + Buff + Clone> BuffClone for T {
-  fn clone_buff(&self) -> Box<Buff> {
+impl<T: 'static + Buff + Clone + Send + Sync> BuffClone for T {
+  fn clone_buff(&self) -> BoxBuff {
     Box::new(self.clone())
   }
 }
 
-impl Clone for Box<Buff> {
-  fn clone(&self) -> Box<Buff> {
+impl Clone for BoxBuff {
+  fn clone(&self) -> BoxBuff {
     self.clone_buff()
   }
 }
@@ -57,16 +156,58 @@ pub trait Buff: BuffClone {
   fn data(&self) -> &BuffData;
   fn mut_data(&mut self) -> &mut BuffData;
   
+  fn kind(&self) -> StatusKind;
+  
   fn reapply_buff(&self, entity: &mut BoxEntity);
   
   fn apply_buff(&self, entity: &mut BoxEntity);
   fn unapply_buff(&self, entity: &mut BoxEntity);
   
+  // What it does to the entity's stats. These are applied to the entity's base stats along with
+  // every other active buff's, rather than changing the stats directly in apply_buff.
+  fn modifiers(&self) -> Vec<StatModifier> {
+    Vec::new()
+  }
+  
+  // Periodic effect, once every tick interval for as long as the buff lasts
+  fn tick(&self, _entity: &mut BoxEntity) {
+  
+  }
+  
   fn should_exist(&self) -> bool {
     self.data().should_exist
   }
   
+  fn timer(&self) -> f32 {
+    self.data().timer
+  }
+  
+  fn stacks(&self) -> u32 {
+    self.data().stacks
+  }
+  
+  fn stack_rule(&self) -> StackRule {
+    self.data().stack_rule
+  }
+  
+  // Another of the same kind landed, see StackRule::Intensity
+  fn add_stack(&mut self, timer: f32) {
+    let stacks = (self.data().stacks + 1).min(self.data().max_stacks);
+    self.mut_data().stacks = stacks;
+    self.mut_data().timer = timer;
+  }
+  
   fn update(&mut self, entity: &mut BoxEntity, delta_time: f32) {
+    if self.data().tick_interval > 0.0 {
+      let interval = self.data().tick_interval;
+      let mut tick_timer = self.data().tick_timer + delta_time.min(self.data().timer);
+      while tick_timer >= interval {
+        tick_timer -= interval;
+        self.tick(entity);
+      }
+      self.mut_data().tick_timer = tick_timer;
+    }
+    
     self.mut_data().timer -= delta_time;
     if self.data().timer <= 0.0 {
       self.mut_data().should_exist = false;
@@ -76,3 +217,127 @@ pub trait Buff: BuffClone {
     }
   }
 }
+
+// Starts buff on entity alongside the buffs already on it, following the stack rule of one of
+// the same kind if there is one. Entities immune to its kind ignore it.
+pub fn add_buff(buffs: &mut Vec<BoxBuff>, buff: BoxBuff, entity: &mut BoxEntity) {
+  if entity.is_immune(buff.kind()) {
+    return;
+  }
+  
+  match buffs.iter().position(|existing| existing.kind() == buff.kind()) {
+    Some(i) => {
+      match buffs[i].stack_rule() {
+        StackRule::Refresh => {
+          buffs[i].unapply_buff(entity);
+          buffs[i] = buff;
+          sync_entity(buffs, entity);
+          buffs[i].apply_buff(entity);
+        },
+        StackRule::Intensity => {
+          buffs[i].add_stack(buff.timer());
+        },
+        StackRule::Unique => {},
+      }
+    },
+    None => {
+      buffs.push(buff);
+      sync_entity(buffs, entity);
+      buffs[buffs.len()-1].apply_buff(entity);
+    },
+  }
+  
+  sync_entity(buffs, entity);
+}
+
+// Ticks every buff on entity, ending the ones that have run out
+pub fn update_buffs(buffs: &mut Vec<BoxBuff>, entity: &mut BoxEntity, delta_time: f32) {
+  let mut offset = 0;
+  for i in 0..buffs.len() {
+    buffs[i-offset].update(entity, delta_time);
+    if !buffs[i-offset].should_exist() {
+      buffs[i-offset].unapply_buff(entity);
+      buffs.remove(i-offset);
+      offset += 1;
+      sync_entity(buffs, entity);
+    }
+  }
+  
+  sync_entity(buffs, entity);
+}
+
+// Recomputes the entity's stats from the buffs on it and updates the statuses it shows
+fn sync_entity(buffs: &Vec<BoxBuff>, entity: &mut BoxEntity) {
+  entity.set_stat_modifiers(buffs.iter().flat_map(|buff| buff.modifiers()).collect());
+  entity.set_statuses(buffs.iter().map(|buff| (buff.kind(), buff.stacks())).collect());
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::modules::entities::{Ship, Sun};
+  
+  use crate::cgmath::Vector2;
+  
+  fn ship() -> BoxEntity {
+    Box::new(Ship::new(Vector2::new(0.0, 0.0)))
+  }
+  
+  #[test]
+  fn refresh_replaces_and_restarts() {
+    let mut entity = ship();
+    let mut buffs: Vec<BoxBuff> = Vec::new();
+    add_buff(&mut buffs, Box::new(Disarm::new().with_timer(4.0)), &mut entity);
+    update_buffs(&mut buffs, &mut entity, 1.0);
+    
+    add_buff(&mut buffs, Box::new(Disarm::new().with_timer(2.0)), &mut entity);
+    assert_eq!(buffs.len(), 1);
+    assert_eq!(buffs[0].timer(), 2.0);
+    assert_eq!(buffs[0].stacks(), 1);
+    assert!(entity.is_disarmed());
+  }
+  
+  #[test]
+  fn intensity_stacks_up_to_max() {
+    let mut entity = ship();
+    let mut buffs: Vec<BoxBuff> = Vec::new();
+    add_buff(&mut buffs, Box::new(Burn::new()), &mut entity);
+    update_buffs(&mut buffs, &mut entity, 1.0);
+    
+    add_buff(&mut buffs, Box::new(Burn::new()), &mut entity);
+    assert_eq!(buffs.len(), 1);
+    assert_eq!(buffs[0].stacks(), 2);
+    assert_eq!(buffs[0].timer(), 3.0);
+    assert_eq!(entity.statuses(), vec!((StatusKind::Burn, 2)));
+    
+    for _ in 0..10 {
+      add_buff(&mut buffs, Box::new(Burn::new()), &mut entity);
+    }
+    assert_eq!(buffs[0].stacks(), buffs[0].data().max_stacks);
+  }
+  
+  #[test]
+  fn unique_ignores_another() {
+    let mut entity = ship();
+    let mut buffs: Vec<BoxBuff> = Vec::new();
+    add_buff(&mut buffs, Box::new(Stun::new().with_timer(1.5)), &mut entity);
+    update_buffs(&mut buffs, &mut entity, 1.0);
+    
+    add_buff(&mut buffs, Box::new(Stun::new().with_timer(5.0)), &mut entity);
+    assert_eq!(buffs.len(), 1);
+    assert_eq!(buffs[0].timer(), 0.5);
+    assert!(entity.is_stunned());
+  }
+  
+  #[test]
+  fn immune_entities_ignore_it() {
+    let mut sun: BoxEntity = Box::new(Sun::new(Vector2::new(0.0, 0.0)));
+    let mut buffs: Vec<BoxBuff> = Vec::new();
+    add_buff(&mut buffs, Box::new(Burn::new()), &mut sun);
+    add_buff(&mut buffs, Box::new(Slow::new()), &mut sun);
+    
+    assert_eq!(buffs.len(), 1);
+    assert_eq!(buffs[0].kind(), StatusKind::Slow);
+    assert!(!sun.statuses().iter().any(|(kind, _)| *kind == StatusKind::Burn));
+  }
+}
@@ -1,6 +1,6 @@
 
 
-use crate::modules::buffs::{Buff, BuffData};
+use crate::modules::buffs::{Buff, BuffData, StatusKind};
 
 use crate::modules::entities::{BoxEntity};
 
@@ -33,6 +33,10 @@ impl Buff for PhaseBuff {
     &mut self.data
   }
   
+  fn kind(&self) -> StatusKind {
+    StatusKind::Phase
+  }
+  
   fn reapply_buff(&self, _entity: &mut BoxEntity) {
     
   }
@@ -0,0 +1,63 @@
+use crate::modules::buffs::{Buff, BuffData, StatusKind, StackRule};
+
+use crate::modules::entities::{BoxEntity};
+
+#[derive(Clone)]
+pub struct Regen {
+  data: BuffData,
+  // health restored each tick, for every stack
+  health: f32,
+}
+
+impl Regen {
+  pub fn new() -> Regen {
+    Regen {
+      data: BuffData::new()
+                      .with_timer(5.0)
+                      .with_tick_interval(1.0)
+                      .with_stack_rule(StackRule::Intensity)
+                      .with_max_stacks(3),
+      health: 1.0,
+    }
+  }
+  
+  pub fn with_health(mut self, health: f32) -> Regen {
+    self.health = health;
+    self
+  }
+  
+  pub fn with_timer(mut self, timer: f32) -> Regen {
+    self.data = self.data.with_timer(timer);
+    self
+  }
+}
+
+impl Buff for Regen {
+  fn data(&self) -> &BuffData {
+    &self.data
+  }
+  
+  fn mut_data(&mut self) -> &mut BuffData {
+    &mut self.data
+  }
+  
+  fn kind(&self) -> StatusKind {
+    StatusKind::Regen
+  }
+  
+  fn reapply_buff(&self, _entity: &mut BoxEntity) {
+  
+  }
+  
+  fn apply_buff(&self, _entity: &mut BoxEntity) {
+  
+  }
+  
+  fn unapply_buff(&self, _entity: &mut BoxEntity) {
+  
+  }
+  
+  fn tick(&self, entity: &mut BoxEntity) {
+    entity.repair(self.health*self.stacks() as f32);
+  }
+}
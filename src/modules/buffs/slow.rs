@@ -0,0 +1,59 @@
+use crate::modules::buffs::{Buff, BuffData, StatusKind};
+
+use crate::modules::entities::{BoxEntity};
+use crate::modules::stats::{Stat, StatModifier};
+
+#[derive(Clone)]
+pub struct Slow {
+  data: BuffData,
+}
+
+impl Slow {
+  pub fn new() -> Slow {
+    Slow {
+      data: BuffData::new()
+                      .with_timer(3.0)
+                      .with_multiplier(0.5),
+    }
+  }
+  
+  pub fn with_multiplier(mut self, multiplier: f32) -> Slow {
+    self.data = self.data.with_multiplier(multiplier);
+    self
+  }
+  
+  pub fn with_timer(mut self, timer: f32) -> Slow {
+    self.data = self.data.with_timer(timer);
+    self
+  }
+}
+
+impl Buff for Slow {
+  fn data(&self) -> &BuffData {
+    &self.data
+  }
+  
+  fn mut_data(&mut self) -> &mut BuffData {
+    &mut self.data
+  }
+  
+  fn kind(&self) -> StatusKind {
+    StatusKind::Slow
+  }
+  
+  fn reapply_buff(&self, _entity: &mut BoxEntity) {
+  
+  }
+  
+  fn apply_buff(&self, _entity: &mut BoxEntity) {
+    
+  }
+  
+  fn unapply_buff(&self, _entity: &mut BoxEntity) {
+    
+  }
+  
+  fn modifiers(&self) -> Vec<StatModifier> {
+    vec!(StatModifier::multiply(Stat::MaxVelocity, self.data().multiplier))
+  }
+}
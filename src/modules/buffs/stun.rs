@@ -0,0 +1,50 @@
+use crate::modules::buffs::{Buff, BuffData, StatusKind, StackRule};
+
+use crate::modules::entities::{BoxEntity};
+
+// Leaves the entity unable to thrust, turn or use any ability
+#[derive(Clone)]
+pub struct Stun {
+  data: BuffData,
+}
+
+impl Stun {
+  pub fn new() -> Stun {
+    Stun {
+      data: BuffData::new()
+                      .with_timer(1.5)
+                      .with_stack_rule(StackRule::Unique),
+    }
+  }
+  
+  pub fn with_timer(mut self, timer: f32) -> Stun {
+    self.data = self.data.with_timer(timer);
+    self
+  }
+}
+
+impl Buff for Stun {
+  fn data(&self) -> &BuffData {
+    &self.data
+  }
+  
+  fn mut_data(&mut self) -> &mut BuffData {
+    &mut self.data
+  }
+  
+  fn kind(&self) -> StatusKind {
+    StatusKind::Stun
+  }
+  
+  fn reapply_buff(&self, _entity: &mut BoxEntity) {
+  
+  }
+  
+  fn apply_buff(&self, entity: &mut BoxEntity) {
+    entity.set_stunned(true);
+  }
+  
+  fn unapply_buff(&self, entity: &mut BoxEntity) {
+    entity.set_stunned(false);
+  }
+}
@@ -0,0 +1,422 @@
+use maat_graphics::DrawCall;
+use maat_graphics::math;
+
+use crate::modules::collisions;
+
+use crate::cgmath::{Vector2, Vector4, InnerSpace};
+
+use std::cmp::Ordering;
+
+// Halvings of the step when searching for a swept time of impact
+const TIME_OF_IMPACT_ITERATIONS: usize = 24;
+
+// A collision shape in world space. Rotations are in degrees, the same as entity rotations,
+// with the box's local x axis pointing along (cos, sin) of it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Collider {
+  Circle { center: Vector2<f32>, radius: f32 },
+  OrientedBox { center: Vector2<f32>, half_size: Vector2<f32>, rotation: f32 },
+  Capsule { start: Vector2<f32>, end: Vector2<f32>, radius: f32 },
+  // any order, only the convex hull of the points is used
+  Polygon { points: Vec<Vector2<f32>> },
+}
+
+impl Collider {
+  pub fn circle(center: Vector2<f32>, radius: f32) -> Collider {
+    Collider::Circle { center, radius }
+  }
+  
+  pub fn oriented_box(center: Vector2<f32>, size: Vector2<f32>, rotation: f32) -> Collider {
+    Collider::OrientedBox { center, half_size: size*0.5, rotation }
+  }
+  
+  pub fn capsule(start: Vector2<f32>, end: Vector2<f32>, radius: f32) -> Collider {
+    Collider::Capsule { start, end, radius }
+  }
+  
+  pub fn polygon(points: Vec<Vector2<f32>>) -> Collider {
+    Collider::Polygon { points }
+  }
+  
+  pub fn translate(&self, offset: Vector2<f32>) -> Collider {
+    match self {
+      Collider::Circle { center, radius } => Collider::circle(center+offset, *radius),
+      Collider::OrientedBox { center, half_size, rotation } => {
+        Collider::OrientedBox { center: center+offset, half_size: *half_size, rotation: *rotation }
+      },
+      Collider::Capsule { start, end, radius } => Collider::capsule(start+offset, end+offset, *radius),
+      Collider::Polygon { points } => Collider::polygon(points.iter().map(|point| point+offset).collect()),
+    }
+  }
+  
+  // min and max corners
+  pub fn bounds(&self) -> (Vector2<f32>, Vector2<f32>) {
+    self.hull().bounds()
+  }
+  
+  pub fn intersects(&self, other: &Collider) -> bool {
+    self.hull().intersects(&other.hull())
+  }
+  
+  pub fn contains_point(&self, point: Vector2<f32>) -> bool {
+    self.hull().contains_point(point)
+  }
+  
+  pub fn contact(&self, other: &Collider) -> Option<Contact> {
+    self.hull().contact(&other.hull())
+  }
+  
+  // Earliest fraction of the step at which the two touch, given where they ended up and the
+  // motion that took them there
+  pub fn time_of_impact(&self, motion: Vector2<f32>, other: &Collider, other_motion: Vector2<f32>) -> Option<f32> {
+    if let (Collider::Circle { center, radius }, Collider::Circle { center: other_center, radius: other_radius }) = (self, other) {
+      return collisions::swept_circle_collision(center.extend(*radius), motion, other_center.extend(*other_radius), other_motion);
+    }
+    
+    // other's frame, at the start of the step
+    let start = self.translate(-motion).hull();
+    let other = other.translate(-other_motion).hull();
+    let motion = motion - other_motion;
+    
+    if start.intersects(&other) {
+      return Some(0.0);
+    }
+    if !start.swept(motion).intersects(&other) {
+      return None;
+    }
+    
+    // sweeping further only ever covers more, so the first touch can be bisected for
+    let mut before = 0.0;
+    let mut after = 1.0;
+    for _ in 0..TIME_OF_IMPACT_ITERATIONS {
+      let time = (before + after)*0.5;
+      if start.swept(motion*time).intersects(&other) {
+        after = time;
+      } else {
+        before = time;
+      }
+    }
+    
+    Some(after)
+  }
+  
+  pub fn draw(&self, colour: Vector4<f32>, draw_calls: &mut Vec<DrawCall>) {
+    match self {
+      Collider::Circle { center, radius } => {
+        draw_calls.push(DrawCall::draw_coloured(*center, Vector2::new(radius*2.0, radius*2.0), colour, 0.0));
+      },
+      Collider::OrientedBox { center, half_size, rotation } => {
+        draw_calls.push(DrawCall::draw_coloured(*center, half_size*2.0, colour, *rotation));
+      },
+      Collider::Capsule { start, end, radius } => {
+        let direction = end-start;
+        let rotation = direction.y.atan2(direction.x).to_degrees();
+        let size = Vector2::new(direction.magnitude(), radius*2.0);
+        draw_calls.push(DrawCall::draw_coloured((start+end)*0.5, size, colour, rotation));
+        draw_calls.push(DrawCall::draw_coloured(*start, Vector2::new(radius*2.0, radius*2.0), colour, 0.0));
+        draw_calls.push(DrawCall::draw_coloured(*end, Vector2::new(radius*2.0, radius*2.0), colour, 0.0));
+      },
+      Collider::Polygon { .. } => {
+        let (min, max) = self.bounds();
+        draw_calls.push(DrawCall::draw_coloured((min+max)*0.5, max-min, colour, 0.0));
+      },
+    }
+  }
+  
+  fn hull(&self) -> RoundedHull {
+    match self {
+      Collider::Circle { center, radius } => RoundedHull { points: vec!(*center), radius: *radius },
+      Collider::OrientedBox { center, half_size, rotation } => {
+        let angle = math::to_radians(*rotation);
+        let x_axis = Vector2::new(angle.cos(), angle.sin())*half_size.x;
+        let y_axis = Vector2::new(-angle.sin(), angle.cos())*half_size.y;
+        let corners = vec!(center-x_axis-y_axis, center+x_axis-y_axis, center+x_axis+y_axis, center-x_axis+y_axis);
+        
+        RoundedHull { points: convex_hull(corners), radius: 0.0 }
+      },
+      Collider::Capsule { start, end, radius } => RoundedHull { points: convex_hull(vec!(*start, *end)), radius: *radius },
+      Collider::Polygon { points } => RoundedHull { points: convex_hull(points.clone()), radius: 0.0 },
+    }
+  }
+}
+
+// How far two touching colliders overlap, and the way to push the second out of the first
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Contact {
+  pub normal: Vector2<f32>,
+  pub depth: f32,
+}
+
+// The deepest contact between any of one object's colliders and any of the other's
+pub fn contact(a: &Vec<Collider>, b: &Vec<Collider>) -> Option<Contact> {
+  let mut deepest: Option<Contact> = None;
+  for a_collider in a {
+    for b_collider in b {
+      if let Some(contact) = a_collider.contact(b_collider) {
+        if deepest.map_or(true, |deepest| contact.depth > deepest.depth) {
+          deepest = Some(contact);
+        }
+      }
+    }
+  }
+  
+  deepest
+}
+
+// Whether any of one object's colliders touches any of the other's
+pub fn colliders_intersect(a: &Vec<Collider>, b: &Vec<Collider>) -> bool {
+  a.iter().any(|a| b.iter().any(|b| a.intersects(b)))
+}
+
+// The box around every collider, swept back over the last step's motion
+pub fn colliders_bounds(colliders: &Vec<Collider>, position: Vector2<f32>, motion: Vector2<f32>) -> (Vector2<f32>, Vector2<f32>) {
+  let mut min = position;
+  let mut max = position;
+  for collider in colliders {
+    let (collider_min, collider_max) = collider.bounds();
+    for &offset in &[Vector2::new(0.0, 0.0), motion] {
+      min.x = min.x.min(collider_min.x - offset.x);
+      min.y = min.y.min(collider_min.y - offset.y);
+      max.x = max.x.max(collider_max.x - offset.x);
+      max.y = max.y.max(collider_max.y - offset.y);
+    }
+  }
+  
+  (min, max)
+}
+
+// Earliest time of impact between any pair of colliders from two swept objects
+pub fn time_of_impact(a: &Vec<Collider>, a_motion: Vector2<f32>, b: &Vec<Collider>, b_motion: Vector2<f32>) -> Option<f32> {
+  let mut earliest: Option<f32> = None;
+  for a_collider in a {
+    for b_collider in b {
+      if let Some(time) = a_collider.time_of_impact(a_motion, b_collider, b_motion) {
+        earliest = Some(earliest.map_or(time, |earliest| earliest.min(time)));
+      }
+    }
+  }
+  
+  earliest
+}
+
+// A convex hull of one or more points, counter clockwise, grown by a radius. Every collider
+// is one of these, a point for circles and a segment for capsules.
+struct RoundedHull {
+  points: Vec<Vector2<f32>>,
+  radius: f32,
+}
+
+impl RoundedHull {
+  fn bounds(&self) -> (Vector2<f32>, Vector2<f32>) {
+    let mut min = self.points[0];
+    let mut max = self.points[0];
+    for point in &self.points {
+      min.x = min.x.min(point.x);
+      min.y = min.y.min(point.y);
+      max.x = max.x.max(point.x);
+      max.y = max.y.max(point.y);
+    }
+    
+    let radius = Vector2::new(self.radius, self.radius);
+    (min-radius, max+radius)
+  }
+  
+  // The hull covering every position between here and motion further on
+  fn swept(&self, motion: Vector2<f32>) -> RoundedHull {
+    let mut points = self.points.clone();
+    points.extend(self.points.iter().map(|point| point+motion));
+    
+    RoundedHull { points: convex_hull(points), radius: self.radius }
+  }
+  
+  fn edges(&self) -> Vec<(Vector2<f32>, Vector2<f32>)> {
+    if self.points.len() < 3 {
+      return vec!((self.points[0], self.points[self.points.len()-1]));
+    }
+    
+    (0..self.points.len()).map(|i| (self.points[i], self.points[(i+1)%self.points.len()])).collect()
+  }
+  
+  fn core_contains(&self, point: Vector2<f32>) -> bool {
+    self.points.len() >= 3 && self.edges().iter().all(|(a, b)| cross(b-a, point-a) >= 0.0)
+  }
+  
+  fn contains_point(&self, point: Vector2<f32>) -> bool {
+    self.core_contains(point) ||
+    self.edges().iter().any(|(a, b)| point_segment_distance(point, *a, *b) <= self.radius)
+  }
+  
+  fn intersects(&self, other: &RoundedHull) -> bool {
+    if self.radius == 0.0 && other.radius == 0.0 && self.points.len() >= 3 && other.points.len() >= 3 {
+      return separating_axis_overlap(self, other);
+    }
+    
+    self.core_distance(other) <= self.radius + other.radius
+  }
+  
+  // Closest distance between the two hulls before either is grown by its radius
+  fn core_distance(&self, other: &RoundedHull) -> f32 {
+    if other.points.iter().any(|point| self.core_contains(*point)) ||
+       self.points.iter().any(|point| other.core_contains(*point)) {
+      return 0.0;
+    }
+    
+    let (from, to) = self.closest_points(other);
+    (to-from).magnitude()
+  }
+  
+  // Closest points between the two hulls' edges, on this one then the other
+  fn closest_points(&self, other: &RoundedHull) -> (Vector2<f32>, Vector2<f32>) {
+    let mut closest = (self.points[0], other.points[0]);
+    let mut distance = std::f32::MAX;
+    for (a, b) in self.edges() {
+      for (c, d) in other.edges() {
+        let (from, to) = segment_closest_points(a, b, c, d);
+        if (to-from).magnitude2() < distance {
+          distance = (to-from).magnitude2();
+          closest = (from, to);
+        }
+      }
+    }
+    
+    closest
+  }
+  
+  fn contact(&self, other: &RoundedHull) -> Option<Contact> {
+    let radius = self.radius + other.radius;
+    let distance = self.core_distance(other);
+    if distance > radius {
+      return None;
+    }
+    
+    if distance > 0.0 {
+      let (from, to) = self.closest_points(other);
+      return Some(Contact { normal: (to-from).normalize(), depth: radius - distance });
+    }
+    
+    // the cores overlap, so push out along whichever edge normal they overlap least on
+    let mut axes = Vec::new();
+    for (a, b) in self.edges().iter().chain(other.edges().iter()) {
+      let edge = b-a;
+      if edge.magnitude2() > 0.0 {
+        axes.push(Vector2::new(-edge.y, edge.x).normalize());
+      }
+    }
+    if axes.is_empty() {
+      // two circles on the same spot
+      axes.push(Vector2::new(0.0, 1.0));
+    }
+    
+    let mut contact = Contact { normal: axes[0], depth: std::f32::MAX };
+    for axis in axes {
+      let (a_min, a_max) = project(&self.points, axis);
+      let (b_min, b_max) = project(&other.points, axis);
+      let (overlap, normal) = if a_max - b_min <= b_max - a_min {
+        (a_max - b_min, axis)
+      } else {
+        (b_max - a_min, -axis)
+      };
+      
+      if overlap + radius < contact.depth {
+        contact = Contact { normal, depth: overlap + radius };
+      }
+    }
+    
+    Some(contact)
+  }
+}
+
+// Two convex polygons overlap unless one of their edge normals separates them
+fn separating_axis_overlap(a: &RoundedHull, b: &RoundedHull) -> bool {
+  for (start, end) in a.edges().iter().chain(b.edges().iter()) {
+    let axis = Vector2::new(start.y-end.y, end.x-start.x);
+    let (a_min, a_max) = project(&a.points, axis);
+    let (b_min, b_max) = project(&b.points, axis);
+    if a_max < b_min || b_max < a_min {
+      return false;
+    }
+  }
+  
+  true
+}
+
+fn project(points: &Vec<Vector2<f32>>, axis: Vector2<f32>) -> (f32, f32) {
+  let mut min = std::f32::MAX;
+  let mut max = std::f32::MIN;
+  for point in points {
+    let distance = point.dot(axis);
+    min = min.min(distance);
+    max = max.max(distance);
+  }
+  
+  (min, max)
+}
+
+fn cross(a: Vector2<f32>, b: Vector2<f32>) -> f32 {
+  a.x*b.y - a.y*b.x
+}
+
+fn closest_point_on_segment(point: Vector2<f32>, a: Vector2<f32>, b: Vector2<f32>) -> Vector2<f32> {
+  let segment = b-a;
+  let length = segment.magnitude2();
+  if length == 0.0 {
+    return a;
+  }
+  
+  let t = ((point-a).dot(segment) / length).max(0.0).min(1.0);
+  a + segment*t
+}
+
+fn point_segment_distance(point: Vector2<f32>, a: Vector2<f32>, b: Vector2<f32>) -> f32 {
+  (point - closest_point_on_segment(point, a, b)).magnitude()
+}
+
+// Closest points on ab then cd, the crossing point for both if they cross
+fn segment_closest_points(a: Vector2<f32>, b: Vector2<f32>, c: Vector2<f32>, d: Vector2<f32>) -> (Vector2<f32>, Vector2<f32>) {
+  let d1 = cross(b-a, c-a);
+  let d2 = cross(b-a, d-a);
+  let d3 = cross(d-c, a-c);
+  let d4 = cross(d-c, b-c);
+  if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0)) &&
+     ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0)) {
+    let crossing = a + (b-a)*(d3 / (d3 - d4));
+    return (crossing, crossing);
+  }
+  
+  let candidates = [(a, closest_point_on_segment(a, c, d)), (b, closest_point_on_segment(b, c, d)),
+                    (closest_point_on_segment(c, a, b), c), (closest_point_on_segment(d, a, b), d)];
+  let mut closest = candidates[0];
+  for candidate in &candidates[1..] {
+    if (candidate.1-candidate.0).magnitude2() < (closest.1-closest.0).magnitude2() {
+      closest = *candidate;
+    }
+  }
+  
+  closest
+}
+
+// Andrew's monotone chain, counter clockwise with no repeated or collinear points. Collinear
+// input comes back as its two ends and a single point as itself.
+fn convex_hull(mut points: Vec<Vector2<f32>>) -> Vec<Vector2<f32>> {
+  points.sort_by(|a, b| a.x.partial_cmp(&b.x).unwrap_or(Ordering::Equal)
+                         .then(a.y.partial_cmp(&b.y).unwrap_or(Ordering::Equal)));
+  points.dedup();
+  if points.len() < 3 {
+    return points;
+  }
+  
+  let mut hull: Vec<Vector2<f32>> = Vec::new();
+  for pass in 0..2 {
+    let start = hull.len();
+    for i in 0..points.len() {
+      let point = if pass == 0 { points[i] } else { points[points.len()-1-i] };
+      while hull.len() >= start+2 && cross(hull[hull.len()-1]-hull[hull.len()-2], point-hull[hull.len()-2]) <= 0.0 {
+        hull.pop();
+      }
+      hull.push(point);
+    }
+    hull.pop();
+  }
+  
+  hull
+}
@@ -29,7 +29,7 @@ pub fn spatial_hash_collision(&self) {
       }
     }
     
-    let entity_groups = spatial_hash.retrieve_possible_entity_collisions();
+    let entity_groups = spatial_hash.retrieve_possible_collisions();
     for group in &entity_groups {
       for i in 0..group.len() {
         for j in i..group.len() {
@@ -50,58 +50,142 @@ pub fn spatial_hash_collision(&self) {
     
     spatial_hash.clear();*/
 
-use crate::modules::spatial_hash::SpatialHash;
+use crate::modules::broadphase::BoxBroadphase;
+use crate::modules::spatial_hash::SpatialObject;
 use crate::modules::entities::MutexEntity;
-use crate::modules::projectiles::MutexProjectile;
+use crate::modules::projectiles::{BoxProjectile, MutexProjectile};
 
-use std::sync::Arc;
+use crate::cgmath::{Vector2, Vector3, InnerSpace};
 
-pub fn collisions(mut entities: Vec<MutexEntity>, mut projectiles: Vec<MutexProjectile>) {
-  let mut spatial_hash = SpatialHash::new(30.0);
+use std::cmp::Ordering;
+
+// Earliest fraction of the step at which two circles moving in straight lines touch. Both are
+// given where they ended up, with the motion that took them there.
+pub fn swept_circle_collision(a: Vector3<f32>, a_motion: Vector2<f32>, b: Vector3<f32>, b_motion: Vector2<f32>) -> Option<f32> {
+  let radius = a.z + b.z;
+  // b's frame, at the start of the step
+  let start = (a.truncate() - a_motion) - (b.truncate() - b_motion);
+  let motion = a_motion - b_motion;
+  
+  // |start + motion*t| = radius, already touching at t = 0 if the constant term isn't positive
+  let qc = start.magnitude2() - radius*radius;
+  if qc <= 0.0 {
+    return Some(0.0);
+  }
+  
+  let qa = motion.magnitude2();
+  if qa == 0.0 {
+    return None;
+  }
+  
+  let qb = 2.0*start.dot(motion);
+  let discriminant = qb*qb - 4.0*qa*qc;
+  if discriminant < 0.0 {
+    return None;
+  }
   
-  for mutex_entity in &entities {
-    spatial_hash.insert_object_for_point(Arc::clone(&mutex_entity));
+  let time = (-qb - discriminant.sqrt()) / (2.0*qa);
+  if time >= 0.0 && time <= 1.0 {
+    Some(time)
+  } else {
+    None
+  }
+}
+
+// The candidates the projectile can hit and sweeps into this step, earliest first, so it stops
+// on the first thing in its path rather than the first thing the broadphase returned
+fn earliest_impacts(projectile: &BoxProjectile, candidates: Vec<MutexEntity>) -> Vec<MutexEntity> {
+  let mut impacts = Vec::new();
+  for entity_mutex in candidates {
+    let time = {
+      let entity = entity_mutex.lock();
+      if entity.should_exist() && projectile.can_hit(entity.hostility()) {
+        projectile.time_of_impact(&*entity)
+      } else {
+        None
+      }
+    };
+    
+    if let Some(time) = time {
+      impacts.push((time, entity_mutex));
+    }
   }
   
+  impacts.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+  impacts.into_iter().map(|(_, entity)| entity).collect()
+}
+
+pub fn collisions(entities: Vec<MutexEntity>, mut projectiles: Vec<MutexProjectile>, broadphase: &mut BoxBroadphase) {
+  broadphase.build(&entities);
+  
    for i in 0..projectiles.len() {
     if let Some(projectile) = &mut projectiles[i].try_lock() {
       if projectile.should_exist() {
         // entity collision 
-        let mut entities = spatial_hash.retrieve_objects(&*projectile);
-        for entity_mutex in &mut entities {
+        let (min, max) = projectile.bounds();
+        let entities = earliest_impacts(&**projectile, broadphase.query(min, max));
+        for entity_mutex in &entities {
           if !projectile.should_exist() {
             break;
           }
           
           let mut entity = entity_mutex.lock();
           if entity.should_exist() {
-            if projectile.can_hit(entity.hostility()) {
-              projectile.collide_with(&mut *entity);
-            }
+            projectile.collide_with(&mut *entity);
           }
         }
       }
     }
   }
   
-  let entity_groups = spatial_hash.retrieve_possible_entity_collisions();
-    for group in &entity_groups {
-      for i in 0..group.len() {
-        for j in i..group.len() {
-          if i == j {
-            continue;
-          }
-          if let Some(mut entity_one) = group[i].try_lock() {
-          if let Some(mut entity_two) = group[j].try_lock() {
-          
-          if entity_one.should_exist() && entity_two.should_exist() &&
-             !entity_one.is_in_phase_mode() && !entity_two.is_in_phase_mode() {
-            entity_one.collide_with(&mut *entity_two);
-            entity_two.collide_with(&mut *entity_one);
-          }
-          }
-          }
+  for (entity_one, entity_two) in &broadphase.pairs() {
+    if let Some(mut entity_one) = entity_one.try_lock() {
+      if let Some(mut entity_two) = entity_two.try_lock() {
+        if entity_one.should_exist() && entity_two.should_exist() &&
+           !entity_one.is_in_phase_mode() && !entity_two.is_in_phase_mode() {
+          entity_one.collide_with(&mut *entity_two);
+          entity_two.collide_with(&mut *entity_one);
         }
       }
     }
+  }
+  
+  broadphase.clear();
+}
+
+// Same pass as collisions, but blocks on every lock instead of skipping busy objects so no
+// pair is ever missed. Only safe to call from the thread that owns the world.
+pub fn lockstep_collisions(entities: &Vec<MutexEntity>, projectiles: &Vec<MutexProjectile>, broadphase: &mut BoxBroadphase) {
+  broadphase.build(entities);
+  
+  for mutex_projectile in projectiles {
+    let mut projectile = mutex_projectile.lock();
+    if projectile.should_exist() {
+      let (min, max) = projectile.bounds();
+      let entities = earliest_impacts(&*projectile, broadphase.query(min, max));
+      for entity_mutex in &entities {
+        if !projectile.should_exist() {
+          break;
+        }
+        
+        let mut entity = entity_mutex.lock();
+        if entity.should_exist() {
+          projectile.collide_with(&mut *entity);
+        }
+      }
+    }
+  }
+  
+  for (entity_one, entity_two) in &broadphase.pairs() {
+    let mut entity_one = entity_one.lock();
+    let mut entity_two = entity_two.lock();
+    
+    if entity_one.should_exist() && entity_two.should_exist() &&
+       !entity_one.is_in_phase_mode() && !entity_two.is_in_phase_mode() {
+      entity_one.collide_with(&mut *entity_two);
+      entity_two.collide_with(&mut *entity_one);
+    }
+  }
+  
+  broadphase.clear();
 }
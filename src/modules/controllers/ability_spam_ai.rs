@@ -33,20 +33,33 @@ impl EntityController for AbilitySpamAi {
     &mut self.data
   }
   
-  fn update(&mut self, ship: &mut BoxEntity, target: Vector2<f32>, _area_pos: Vector2<f32>, _area_size: Vector2<f32>, window_size: Vector2<f32>, delta_time: f32) {
+  fn update(&mut self, ship: &mut BoxEntity, target: Option<Vector2<f32>>, _area_pos: Vector2<f32>, _area_size: Vector2<f32>, window_size: Vector2<f32>, delta_time: f32) {
     let hostility = ship.hostility().clone();
+    let position = ship.position();
     
-    for ability in &mut self.mut_data().abilities {
-      ability.update(delta_time);
-      ability.activate(ship, target, window_size, &hostility);
+    let loadout = &mut self.mut_data().loadout;
+    loadout.update(delta_time);
+    for i in 0..loadout.len() {
+      match target {
+        Some(target) => loadout.activate(i, ship, target, window_size, &hostility),
+        None if loadout.ability(i).map(|ability| ability.range() == 0.0).unwrap_or(false) => {
+          loadout.activate(i, ship, position, window_size, &hostility);
+        },
+        None => {},
+      }
     }
     
+    let target = match target {
+      Some(target) => target,
+      None => return,
+    };
+    
     let mut vel_dir = target - ship.position();
     if vel_dir.magnitude() < 400.0 {
       vel_dir = math::rotate_vector2(vel_dir, 90.0);
     }
     ship.apply_acceleration_in_direction(vel_dir);
     
-    ship.set_facing(target);
+    ship.steer_towards(target);
   }
 }
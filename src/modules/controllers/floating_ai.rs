@@ -37,7 +37,7 @@ impl EntityController for FloatingAi {
     &mut self.data
   }
   
-  fn update(&mut self, ship: &mut BoxEntity, _target: Vector2<f32>, area_pos: Vector2<f32>, area_size: Vector2<f32>, _window_size: Vector2<f32>, delta_time: f32) {
+  fn update(&mut self, ship: &mut BoxEntity, _target: Option<Vector2<f32>>, area_pos: Vector2<f32>, area_size: Vector2<f32>, _window_size: Vector2<f32>, delta_time: f32) {
     let position = ship.position();
     
     if (position - area_pos).magnitude() > (area_size*0.5).magnitude() {
@@ -0,0 +1,189 @@
+use crate::modules::entities::{BoxEntity};
+use crate::modules::controllers::{EntityController, EntityControllerData};
+use crate::modules::abilities::{BoxAbility};
+use crate::modules::scripting::ScriptApi;
+
+use crate::cgmath::Vector2;
+
+use hlua::{self, Lua, LuaFunction};
+
+use std::cell::RefCell;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+#[derive(Clone)]
+enum LuaCommand {
+  Accelerate(Vector2<f32>),
+  Face(Vector2<f32>),
+  Activate(usize),
+}
+
+// Hands control of an entity to a Lua script. Each tick the script's global update(delta_time)
+// is called with the ship, target (nil with nothing in sight), area and cooldowns tables set, and the commands it issues
+// through accelerate(x, y), face(x, y) and activate(n) are applied in order afterwards.
+#[derive(Clone)]
+pub struct LuaController {
+  data: EntityControllerData,
+  script: String,
+  lua: Rc<RefCell<Lua<'static>>>,
+  commands: Rc<RefCell<Vec<LuaCommand>>>,
+  // the world whose seeded random and math.random are registered in lua
+  scripting: Option<ScriptApi>,
+}
+
+impl LuaController {
+  pub fn new(script: String, source: &str) -> Result<LuaController, String> {
+    let mut lua = Lua::new();
+    lua.openlibs();
+    
+    let commands = Rc::new(RefCell::new(Vec::new()));
+    
+    let queue = Rc::clone(&commands);
+    lua.set("accelerate", hlua::function2(move |x: f32, y: f32| {
+      queue.borrow_mut().push(LuaCommand::Accelerate(Vector2::new(x, y)));
+    }));
+    
+    let queue = Rc::clone(&commands);
+    lua.set("face", hlua::function2(move |x: f32, y: f32| {
+      queue.borrow_mut().push(LuaCommand::Face(Vector2::new(x, y)));
+    }));
+    
+    // abilities are numbered from 1 like any other lua array
+    let queue = Rc::clone(&commands);
+    lua.set("activate", hlua::function1(move |ability: u32| {
+      if ability > 0 {
+        queue.borrow_mut().push(LuaCommand::Activate(ability as usize - 1));
+      }
+    }));
+    
+    lua.execute::<()>(source).map_err(|e| format!("{}: {:?}", script, e))?;
+    {
+      let update: Option<LuaFunction<_>> = lua.get("update");
+      if update.is_none() {
+        return Err(format!("{}: no update function", script));
+      }
+    }
+    
+    Ok(LuaController {
+      data: EntityControllerData::new(),
+      script,
+      lua: Rc::new(RefCell::new(lua)),
+      commands,
+      scripting: None,
+    })
+  }
+  
+  pub fn load<P: AsRef<Path>>(path: P) -> Result<LuaController, String> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    
+    LuaController::new(path.display().to_string(), &source)
+  }
+  
+  pub fn with_ability(mut self, ability: BoxAbility) -> LuaController {
+    self.data = self.data.with_ability(ability);
+    self
+  }
+  
+  fn set_globals(&self, lua: &mut Lua, ship: &BoxEntity, target: Option<Vector2<f32>>, area_pos: Vector2<f32>, area_size: Vector2<f32>) {
+    {
+      let mut table = lua.empty_array("ship");
+      table.set("x", ship.position().x);
+      table.set("y", ship.position().y);
+      table.set("vx", ship.velocity().x);
+      table.set("vy", ship.velocity().y);
+      table.set("rotation", ship.rotation());
+      table.set("health", ship.health());
+      table.set("max_health", ship.max_health());
+    }
+    
+    // nil when there is nothing in sight
+    match target {
+      Some(target) => {
+        let mut table = lua.empty_array("target");
+        table.set("x", target.x);
+        table.set("y", target.y);
+      },
+      None => {
+        lua.set("target", hlua::AnyLuaValue::LuaNil);
+      },
+    }
+    
+    {
+      let mut table = lua.empty_array("area");
+      table.set("x", area_pos.x);
+      table.set("y", area_pos.y);
+      table.set("width", area_size.x);
+      table.set("height", area_size.y);
+    }
+    
+    {
+      let mut table = lua.empty_array("cooldowns");
+      for (i, ability) in self.data.loadout.abilities().iter().enumerate() {
+        table.set(i as u32 + 1, ability.percentage_cooldown_left());
+      }
+    }
+  }
+}
+
+impl EntityController for LuaController {
+  fn data(&self) -> &EntityControllerData {
+    &self.data
+  }
+  
+  fn mut_data(&mut self) -> &mut EntityControllerData {
+    &mut self.data
+  }
+  
+  fn attach_scripting(&mut self, scripting: &ScriptApi) {
+    if self.scripting.as_ref().map_or(false, |attached| attached.is_same(scripting)) {
+      return;
+    }
+    
+    scripting.register_random(&mut self.lua.borrow_mut());
+    self.scripting = Some(scripting.clone());
+  }
+  
+  fn update(&mut self, ship: &mut BoxEntity, target: Option<Vector2<f32>>, area_pos: Vector2<f32>, area_size: Vector2<f32>, window_size: Vector2<f32>, delta_time: f32) {
+    self.mut_data().loadout.update(delta_time);
+    
+    {
+      let lua = Rc::clone(&self.lua);
+      let mut lua = lua.borrow_mut();
+      self.set_globals(&mut lua, ship, target, area_pos, area_size);
+      
+      let function: Option<LuaFunction<_>> = lua.get("update");
+      if let Some(mut function) = function {
+        let result: Result<(), _> = function.call_with_args(delta_time);
+        if let Err(e) = result {
+          println!("Lua controller {} failed: {:?}", self.script, e);
+        }
+      }
+    }
+    
+    let hostility = ship.hostility().clone();
+    let commands: Vec<LuaCommand> = self.commands.borrow_mut().drain(..).collect();
+    for command in commands {
+      match command {
+        LuaCommand::Accelerate(direction) => {
+          ship.apply_acceleration_in_direction(direction);
+        },
+        LuaCommand::Face(position) => {
+          ship.steer_towards(position);
+        },
+        LuaCommand::Activate(index) => {
+          let loadout = &mut self.mut_data().loadout;
+          match target {
+            Some(target) => loadout.activate(index, ship, target, window_size, &hostility),
+            None if loadout.ability(index).map(|ability| ability.range() == 0.0).unwrap_or(false) => {
+              let position = ship.position();
+              loadout.activate(index, ship, position, window_size, &hostility);
+            },
+            None => {},
+          }
+        },
+      }
+    }
+  }
+}
@@ -1,13 +1,22 @@
 pub use self::ability_spam_ai::AbilitySpamAi;
 pub use self::idle_ai::IdleAi;
 pub use self::floating_ai::FloatingAi;
+pub use self::lua_controller::LuaController;
+pub use self::state_machine_ai::{StateMachineAi, AiState};
+pub use self::target_view::{TargetView, SIGHT_RANGE};
+pub use self::wingman_ai::WingmanAi;
 
 mod ability_spam_ai;
 mod idle_ai;
 mod floating_ai;
+mod lua_controller;
+mod state_machine_ai;
+mod target_view;
+mod wingman_ai;
 
-use crate::modules::abilities::{BoxAbility};
+use crate::modules::abilities::{BoxAbility, Loadout};
 use crate::modules::entities::{BoxEntity};
+use crate::modules::scripting::ScriptApi;
 
 use crate::cgmath::Vector2;
 
@@ -15,18 +24,19 @@ pub type BoxEntityController = Box<EntityController>;
 
 #[derive(Clone)]
 pub struct EntityControllerData {
-  abilities: Vec<BoxAbility>,
+  loadout: Loadout,
 }
 
 impl EntityControllerData {
   pub fn new() -> EntityControllerData {
     EntityControllerData {
-      abilities: Vec::new(),
+      loadout: Loadout::new(),
     }
   }
   
+  // Fitted to the ship's slots when the controller takes it over, see Loadout::refit
   pub fn with_ability(mut self, ability: BoxAbility) -> EntityControllerData {
-    self.abilities.push(ability);
+    self.loadout = self.loadout.with_ability(ability);
     self
   }
 }
@@ -51,5 +61,27 @@ pub trait EntityController: EntityControllerClone {
   fn data(&self) -> &EntityControllerData;
   fn mut_data(&mut self) -> &mut EntityControllerData;
   
-  fn update(&mut self, ship: &mut BoxEntity, target: Vector2<f32>, area_pos: Vector2<f32>, area_size: Vector2<f32>, window_size: Vector2<f32>, delta_time: f32);
+  // Called every tick before update with the world's script api, for controllers that run
+  // scripts of their own
+  fn attach_scripting(&mut self, _scripting: &ScriptApi) {
+    
+  }
+  
+  // Called every tick before update with what the ship can see, returns where it should aim.
+  // None when there is nothing in sight to shoot at.
+  fn select_target(&mut self, ship: &BoxEntity, targets: &TargetView) -> Option<Vector2<f32>> {
+    targets.select_target(ship, SIGHT_RANGE).map(|entity| entity.position)
+  }
+  
+  // Called once with the ship it will control
+  fn take_control(&mut self, ship: &mut BoxEntity) {
+    self.mut_data().loadout.refit(ship);
+  }
+  
+  // Called every tick before update, so the abilities follow what is in the ship's slots
+  fn sync_abilities(&mut self, ship: &BoxEntity) {
+    self.mut_data().loadout.sync(ship);
+  }
+  
+  fn update(&mut self, ship: &mut BoxEntity, target: Option<Vector2<f32>>, area_pos: Vector2<f32>, area_size: Vector2<f32>, window_size: Vector2<f32>, delta_time: f32);
 }
@@ -0,0 +1,167 @@
+use maat_graphics::math;
+
+use crate::modules::entities::{BoxEntity};
+use crate::modules::controllers::{EntityController, EntityControllerData};
+use crate::modules::abilities::{BoxAbility};
+
+use crate::cgmath::{Vector2, InnerSpace};
+
+// Used when none of the abilities fire a projectile
+const DEFAULT_RANGE: f32 = 400.0;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AiState {
+  // Closing in on the target until it is in weapon range
+  Pursue,
+  // Circling the target while firing
+  Strafe,
+  // Running from the target after taking too much damage
+  Flee,
+  // Heading back to the centre of the area, repairing there if it has a repair bay
+  Retreat,
+}
+
+// Enemy ai that picks what to do from its health, the target's distance and whether it has
+// strayed out of its area. Abilities that fire projectiles are only used when the target is
+// close enough for the projectile to reach it.
+#[derive(Clone)]
+pub struct StateMachineAi {
+  data: EntityControllerData,
+  state: AiState,
+  flee_health: f32,
+  recover_health: f32,
+}
+
+impl StateMachineAi {
+  pub fn new() -> StateMachineAi {
+    StateMachineAi {
+      data: EntityControllerData::new(),
+      state: AiState::Pursue,
+      flee_health: 0.3,
+      recover_health: 0.9,
+    }
+  }
+  
+  pub fn with_ability(mut self, ability: BoxAbility) -> StateMachineAi {
+    self.data = self.data.with_ability(ability);
+    self
+  }
+  
+  // Fraction of max health below which the ship flees
+  pub fn with_flee_health(mut self, fraction: f32) -> StateMachineAi {
+    self.flee_health = fraction;
+    self
+  }
+  
+  // Fraction of max health the ship repairs to before it goes back to fighting
+  pub fn with_recover_health(mut self, fraction: f32) -> StateMachineAi {
+    self.recover_health = fraction;
+    self
+  }
+  
+  pub fn state(&self) -> AiState {
+    self.state
+  }
+  
+  // The longest reach of any ability that fires a projectile
+  fn weapon_range(&self) -> f32 {
+    let range = self.data.loadout.abilities().iter().map(|ability| ability.range()).fold(0.0, f32::max);
+    if range > 0.0 {
+      range
+    } else {
+      DEFAULT_RANGE
+    }
+  }
+  
+  fn next_state(&self, ship: &BoxEntity, distance: f32, range: f32, in_area: bool, at_home: bool) -> AiState {
+    let health = ship.health() / ship.max_health();
+    
+    match self.state {
+      AiState::Pursue | AiState::Strafe if health < self.flee_health => AiState::Flee,
+      _ if !in_area => AiState::Retreat,
+      AiState::Pursue if distance <= range*0.8 => AiState::Strafe,
+      AiState::Strafe if distance > range => AiState::Pursue,
+      AiState::Flee if distance > range*1.5 => AiState::Retreat,
+      AiState::Retreat if at_home && (health >= self.recover_health || ship.repair_rate() == 0.0) => AiState::Pursue,
+      state => state,
+    }
+  }
+}
+
+impl EntityController for StateMachineAi {
+  fn data(&self) -> &EntityControllerData {
+    &self.data
+  }
+  
+  fn mut_data(&mut self) -> &mut EntityControllerData {
+    &mut self.data
+  }
+  
+  fn update(&mut self, ship: &mut BoxEntity, target: Option<Vector2<f32>>, area_pos: Vector2<f32>, area_size: Vector2<f32>, window_size: Vector2<f32>, delta_time: f32) {
+    let position = ship.position();
+    // with nothing in sight it is as if the target were out of reach
+    let distance = target.map(|target| (target - position).magnitude()).unwrap_or(f32::INFINITY);
+    let range = self.weapon_range();
+    
+    let from_home = position - area_pos;
+    let in_area = from_home.x.abs() <= area_size.x*0.5 && from_home.y.abs() <= area_size.y*0.5;
+    let at_home = from_home.x.abs() <= area_size.x*0.25 && from_home.y.abs() <= area_size.y*0.25;
+    
+    self.state = self.next_state(ship, distance, range, in_area, at_home);
+    
+    match (self.state, target) {
+      (AiState::Pursue, Some(target)) => {
+        ship.apply_acceleration_in_direction(target - position);
+        ship.steer_towards(target);
+      },
+      (AiState::Strafe, Some(target)) => {
+        let to_target = target - position;
+        let mut direction = math::rotate_vector2(to_target, 90.0);
+        if distance < range*0.5 {
+          direction -= math::normalise_vector2(to_target)*direction.magnitude();
+        }
+        ship.apply_acceleration_in_direction(direction);
+        ship.steer_towards(target);
+      },
+      (AiState::Flee, Some(target)) => {
+        ship.apply_acceleration_in_direction(position - target);
+        ship.steer_towards(target);
+      },
+      (AiState::Retreat, _) => {
+        if at_home {
+          ship.apply_acceleration_in_direction(-ship.velocity());
+          let repair_rate = ship.repair_rate();
+          ship.repair(repair_rate*delta_time);
+        } else {
+          ship.apply_acceleration_in_direction(area_pos - position);
+        }
+        ship.steer_towards(area_pos);
+      },
+      (_, None) => {
+        ship.apply_acceleration_in_direction(-ship.velocity());
+      },
+    }
+    
+    let hostility = ship.hostility().clone();
+    let firing = self.state != AiState::Retreat;
+    
+    let loadout = &mut self.mut_data().loadout;
+    loadout.update(delta_time);
+    if !firing {
+      return;
+    }
+    
+    for i in 0..loadout.len() {
+      let ability_range = loadout.abilities()[i].range();
+      match target {
+        Some(target) if ability_range == 0.0 || distance <= ability_range => {
+          loadout.activate(i, ship, target, window_size, &hostility);
+        },
+        None if ability_range == 0.0 => {
+          loadout.activate(i, ship, position, window_size, &hostility);
+        },
+        _ => {},
+      }
+    }
+  }
+}
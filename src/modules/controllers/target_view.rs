@@ -0,0 +1,86 @@
+use crate::modules::entities::{BoxEntity};
+use crate::modules::scripting::EntitySnapshot;
+use crate::modules::spatial_hash::SpatialHash;
+
+use crate::cgmath::{Vector2, InnerSpace};
+
+// How far a controller looks for something to shoot at
+pub const SIGHT_RANGE: f32 = 2000.0;
+
+const CELL_SIZE: f32 = 500.0;
+
+// What controllers can see of the world this tick, snapshots of every entity in a spatial
+// hash so nearby lookups don't walk the whole world. Built once per tick by the World
+// before any area updates, so a controller can query it while its own entity is locked.
+#[derive(Clone)]
+pub struct TargetView {
+  player_id: u32,
+  entities: Vec<EntitySnapshot>,
+  // indices into entities
+  spatial_hash: SpatialHash<usize>,
+}
+
+impl TargetView {
+  pub fn new(entities: Vec<EntitySnapshot>, player_id: u32) -> TargetView {
+    let mut spatial_hash = SpatialHash::new(CELL_SIZE);
+    for i in 0..entities.len() {
+      spatial_hash.insert(i, entities[i].position, entities[i].position);
+    }
+    
+    TargetView {
+      player_id,
+      entities,
+      spatial_hash,
+    }
+  }
+  
+  pub fn entities(&self) -> &Vec<EntitySnapshot> {
+    &self.entities
+  }
+  
+  pub fn entity(&self, id: u32) -> Option<&EntitySnapshot> {
+    self.entities.iter().find(|entity| entity.id == id)
+  }
+  
+  pub fn player(&self) -> Option<&EntitySnapshot> {
+    self.entity(self.player_id)
+  }
+  
+  // Every entity within radius of the position, in the order they were added to the view
+  pub fn nearby(&self, position: Vector2<f32>, radius: f32) -> Vec<&EntitySnapshot> {
+    let indices = self.spatial_hash.retrieve(position - Vector2::new(radius, radius), position + Vector2::new(radius, radius));
+    
+    indices.into_iter()
+           .map(|i| &self.entities[i])
+           .filter(|entity| (entity.position - position).magnitude() <= radius)
+           .collect()
+  }
+  
+  // The closest entity within radius on the opposing side
+  pub fn nearest_enemy(&self, ship: &BoxEntity, radius: f32) -> Option<&EntitySnapshot> {
+    let position = ship.position();
+    let mut nearest: Option<(&EntitySnapshot, f32)> = None;
+    for entity in self.nearby(position, radius) {
+      if entity.id == ship.id() || entity.health <= 0.0 || !ship.hostility().is_enemy_of(&entity.hostility) {
+        continue;
+      }
+      
+      let distance = (entity.position - position).magnitude();
+      if nearest.map(|(_, nearest_distance)| distance < nearest_distance).unwrap_or(true) {
+        nearest = Some((entity, distance));
+      }
+    }
+    
+    nearest.map(|(entity, _)| entity)
+  }
+  
+  // Neutral ships only fight back against whoever last hit them, everything else goes for
+  // the nearest thing it can hurt.
+  pub fn select_target(&self, ship: &BoxEntity, radius: f32) -> Option<&EntitySnapshot> {
+    if ship.hostility().is_neutral() {
+      ship.last_attacker().and_then(|id| self.entity(id))
+    } else {
+      self.nearest_enemy(ship, radius)
+    }
+  }
+}
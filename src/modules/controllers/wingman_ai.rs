@@ -0,0 +1,123 @@
+use crate::modules::entities::{BoxEntity};
+use crate::modules::controllers::{EntityController, EntityControllerData, TargetView};
+use crate::modules::abilities::{BoxAbility};
+use crate::modules::scripting::EntitySnapshot;
+
+use crate::cgmath::{Vector2, InnerSpace};
+
+// Flies in formation with the player, shooting at hostiles that come close enough and
+// breaking off to regroup whenever the player gets too far ahead.
+#[derive(Clone)]
+pub struct WingmanAi {
+  data: EntityControllerData,
+  // in the player's frame, y points the way the player is facing
+  offset: Vector2<f32>,
+  engage_range: f32,
+  regroup_distance: f32,
+  leader: Option<EntitySnapshot>,
+  regrouping: bool,
+}
+
+impl WingmanAi {
+  pub fn new(offset: Vector2<f32>) -> WingmanAi {
+    WingmanAi {
+      data: EntityControllerData::new(),
+      offset,
+      engage_range: 900.0,
+      regroup_distance: 700.0,
+      leader: None,
+      regrouping: false,
+    }
+  }
+  
+  pub fn with_ability(mut self, ability: BoxAbility) -> WingmanAi {
+    self.data = self.data.with_ability(ability);
+    self
+  }
+  
+  // How close a hostile has to get to the wingman before it opens fire
+  pub fn with_engage_range(mut self, range: f32) -> WingmanAi {
+    self.engage_range = range;
+    self
+  }
+  
+  // How far from its slot the wingman can get before it stops fighting to catch up
+  pub fn with_regroup_distance(mut self, distance: f32) -> WingmanAi {
+    self.regroup_distance = distance;
+    self
+  }
+  
+  // Where the wingman should be, the offset turned to match the player's rotation
+  fn slot(&self) -> Option<Vector2<f32>> {
+    self.leader.as_ref().map(|leader| {
+      let (sin, cos) = leader.rotation.to_radians().sin_cos();
+      let offset = Vector2::new(self.offset.x*cos - self.offset.y*sin, self.offset.x*sin + self.offset.y*cos);
+      leader.position + offset
+    })
+  }
+}
+
+impl EntityController for WingmanAi {
+  fn data(&self) -> &EntityControllerData {
+    &self.data
+  }
+  
+  fn mut_data(&mut self) -> &mut EntityControllerData {
+    &mut self.data
+  }
+  
+  fn select_target(&mut self, ship: &BoxEntity, targets: &TargetView) -> Option<Vector2<f32>> {
+    self.leader = targets.player().cloned();
+    
+    if let Some(slot) = self.slot() {
+      let from_slot = (slot - ship.position()).magnitude();
+      if from_slot > self.regroup_distance {
+        self.regrouping = true;
+      } else if from_slot < self.regroup_distance*0.25 {
+        self.regrouping = false;
+      }
+    }
+    
+    if self.regrouping {
+      None
+    } else {
+      targets.nearest_enemy(ship, self.engage_range).map(|entity| entity.position)
+    }
+  }
+  
+  fn update(&mut self, ship: &mut BoxEntity, target: Option<Vector2<f32>>, _area_pos: Vector2<f32>, _area_size: Vector2<f32>, window_size: Vector2<f32>, delta_time: f32) {
+    self.mut_data().loadout.update(delta_time);
+    
+    let (slot, leader_velocity, leader_rotation) = match (self.slot(), &self.leader) {
+      (Some(slot), Some(leader)) => (slot, leader.velocity, leader.rotation),
+      _ => return,
+    };
+    
+    let to_slot = slot - ship.position();
+    if to_slot.magnitude() > 25.0 {
+      ship.apply_acceleration_in_direction(to_slot);
+    } else if leader_velocity.magnitude() > 1.0 {
+      ship.apply_acceleration_in_direction(leader_velocity);
+    }
+    
+    let target = match target {
+      Some(target) => target,
+      None => {
+        ship.set_heading(leader_rotation);
+        return;
+      },
+    };
+    
+    ship.steer_towards(target);
+    
+    let hostility = ship.hostility().clone();
+    let distance = (target - ship.position()).magnitude();
+    let loadout = &mut self.mut_data().loadout;
+    for i in 0..loadout.len() {
+      let range = loadout.abilities()[i].range();
+      if range == 0.0 || distance <= range {
+        loadout.activate(i, ship, target, window_size, &hostility);
+      }
+    }
+  }
+}
@@ -0,0 +1,102 @@
+use serde::Deserialize;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DamageType {
+  Kinetic,
+  Thermal,
+  Energy,
+  Collision,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Damage {
+  amount: f32,
+  damage_type: DamageType,
+  // id of the entity responsible, if any
+  source: Option<u32>,
+}
+
+impl Damage {
+  pub fn new(amount: f32, damage_type: DamageType) -> Damage {
+    Damage {
+      amount,
+      damage_type,
+      source: None,
+    }
+  }
+  
+  // 0 is the id of nothing, so leaves it without a source
+  pub fn with_source(mut self, id: u32) -> Damage {
+    self.source = if id == 0 { None } else { Some(id) };
+    self
+  }
+  
+  pub fn with_amount(mut self, amount: f32) -> Damage {
+    self.amount = amount;
+    self
+  }
+  
+  pub fn amount(&self) -> f32 {
+    self.amount
+  }
+  
+  pub fn damage_type(&self) -> DamageType {
+    self.damage_type
+  }
+  
+  pub fn source(&self) -> Option<u32> {
+    self.source
+  }
+}
+
+// Fraction of each type of damage shrugged off, negative for a weakness that takes extra.
+// Types a ship file leaves out get no resistance.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Resistances {
+  kinetic: f32,
+  thermal: f32,
+  energy: f32,
+  collision: f32,
+}
+
+impl Resistances {
+  pub fn new() -> Resistances {
+    Resistances {
+      kinetic: 0.0,
+      thermal: 0.0,
+      energy: 0.0,
+      collision: 0.0,
+    }
+  }
+  
+  pub fn with_resistance(mut self, damage_type: DamageType, resistance: f32) -> Resistances {
+    match damage_type {
+      DamageType::Kinetic => self.kinetic = resistance,
+      DamageType::Thermal => self.thermal = resistance,
+      DamageType::Energy => self.energy = resistance,
+      DamageType::Collision => self.collision = resistance,
+    }
+    self
+  }
+  
+  pub fn resistance(&self, damage_type: DamageType) -> f32 {
+    match damage_type {
+      DamageType::Kinetic => self.kinetic,
+      DamageType::Thermal => self.thermal,
+      DamageType::Energy => self.energy,
+      DamageType::Collision => self.collision,
+    }
+  }
+  
+  // How much of the damage gets through, never less than nothing
+  pub fn reduce(&self, damage: &Damage) -> f32 {
+    (damage.amount()*(1.0 - self.resistance(damage.damage_type()))).max(0.0)
+  }
+}
+
+impl Default for Resistances {
+  fn default() -> Resistances {
+    Resistances::new()
+  }
+}
@@ -1,4 +1,5 @@
 use crate::modules::entities::{Entity, BoxEntity, EntityData};
+use crate::modules::damage::{DamageType, Resistances};
 
 use crate::cgmath::Vector2;
 
@@ -18,7 +19,10 @@ impl Astroid {
       data: EntityData::new(position, size, texture.to_string())
                         .with_max_velocity(300.0)
                         .with_inertia(0.59)
-                        .with_health(150.0),
+                        .with_health(150.0)
+                        .with_resistances(Resistances::new().with_resistance(DamageType::Kinetic, 0.4)
+                                                            .with_resistance(DamageType::Collision, 0.5)
+                                                            .with_resistance(DamageType::Thermal, -0.25)),
     }
   }
   
@@ -53,12 +57,9 @@ impl Entity for Astroid {
     vec!((Vector2::new(0.0, 0.0), radius))
   }
   
-  fn collide_with(&mut self, entity: &mut BoxEntity) {
-    let dmg = if entity.texture() == self.texture() {
-      0.0
-    } else {
-      0.5
-    };
-    self.entity_collision(entity, dmg, 500.0);
+  fn collide_with(&mut self, entity: &mut BoxEntity) -> Option<f32> {
+    // astroids knock each other about without breaking
+    let harmful = entity.texture() != self.texture();
+    self.entity_collision(entity, harmful)
   }
 }
@@ -0,0 +1,242 @@
+use crate::modules::entities::sections::{ShipSection, ModularSlot, Hull, Wing, Thruster, WeaponMount, RepairBay, HullMaterial, Reactor};
+use crate::modules::entities::{Entity, BoxEntity, EntityData};
+use crate::modules::areas::AbilityDefinition;
+use crate::modules::damage::Resistances;
+
+use crate::cgmath::Vector2;
+
+use serde::Deserialize;
+
+use std::fs;
+use std::path::Path;
+
+pub const SHIP_DIRECTORY: &str = "./resources/ships";
+
+#[derive(Clone, Deserialize)]
+pub enum SectionKind {
+  Hull,
+  Wing,
+  Thruster,
+  WeaponMount,
+  RepairBay,
+  HullMaterial,
+  Reactor,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct SlotDefinition {
+  pub id: i32,
+  // the slot this one fires together with
+  #[serde(default)]
+  pub linked: Option<i32>,
+  #[serde(default)]
+  pub ability: Option<AbilityDefinition>,
+}
+
+impl SlotDefinition {
+  pub fn to_slot(&self) -> ModularSlot {
+    let slot = ModularSlot::new(self.id, self.linked);
+    match self.ability.as_ref().map(|ability| ability.to_ability()) {
+      Some(Ok(ability)) => slot.with_ability(ability),
+      Some(Err(e)) => {
+        println!("Slot {} left empty, {}", self.id, e);
+        slot
+      },
+      None => slot,
+    }
+  }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct SectionDefinition {
+  pub kind: SectionKind,
+  pub offset: (f32, f32),
+  pub size: (f32, f32),
+  // defaults to one scaled by the section's area
+  #[serde(default)]
+  pub health: Option<f32>,
+  #[serde(default)]
+  pub slots: Vec<SlotDefinition>,
+  // only for hull material, which otherwise uses its default plating
+  #[serde(default)]
+  pub resistances: Option<Resistances>,
+}
+
+impl SectionDefinition {
+  pub fn to_section(&self) -> Box<ShipSection> {
+    let offset = Vector2::new(self.offset.0, self.offset.1);
+    let size = Vector2::new(self.size.0, self.size.1);
+    
+    let mut section: Box<ShipSection> = match self.kind {
+      SectionKind::Hull => Box::new(Hull::new(offset, size)),
+      SectionKind::Wing => Box::new(Wing::new(offset, size)),
+      SectionKind::Thruster => Box::new(Thruster::new(offset, size)),
+      SectionKind::WeaponMount => Box::new(WeaponMount::new(offset, size)),
+      SectionKind::RepairBay => Box::new(RepairBay::new(offset, size)),
+      SectionKind::HullMaterial => {
+        let hull_material = HullMaterial::new(offset, size);
+        match self.resistances {
+          Some(resistances) => Box::new(hull_material.with_resistances(resistances)),
+          None => Box::new(hull_material),
+        }
+      },
+      SectionKind::Reactor => Box::new(Reactor::new(offset, size)),
+    };
+    
+    if let Some(health) = self.health {
+      section.set_max_health(health);
+    }
+    
+    for slot in &self.slots {
+      section.add_modular_slot(slot.to_slot());
+    }
+    
+    section
+  }
+}
+
+fn default_collision_scale() -> f32 {
+  0.7
+}
+
+// A ship described in a .ron file in resources/ships, see bulbz.ron for the layout.
+// texture_path is only needed for textures the game doesn't already load.
+#[derive(Clone, Deserialize)]
+pub struct ShipDefinition {
+  pub name: String,
+  pub texture: String,
+  #[serde(default)]
+  pub texture_path: Option<String>,
+  pub size: (f32, f32),
+  pub max_velocity: f32,
+  pub inertia: f32,
+  pub health: f32,
+  #[serde(default)]
+  pub shield: f32,
+  // seconds after a hit before the shield regenerates
+  #[serde(default)]
+  pub shield_delay: Option<f32>,
+  // split the shield into front and rear arcs
+  #[serde(default)]
+  pub shield_arcs: bool,
+  #[serde(default = "default_collision_scale")]
+  pub collision_scale: f32,
+  #[serde(default)]
+  pub sections: Vec<SectionDefinition>,
+}
+
+impl ShipDefinition {
+  pub fn load<P: AsRef<Path>>(path: P) -> Result<ShipDefinition, String> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    
+    ron::de::from_str(&contents).map_err(|e| format!("{}: {}", path.display(), e))
+  }
+  
+  // Every .ron file in the directory sorted by file name, files that fail to parse are
+  // reported and skipped so one bad hull doesn't take the rest down with it.
+  pub fn load_all<P: AsRef<Path>>(directory: P) -> Vec<ShipDefinition> {
+    let mut paths = Vec::new();
+    if let Ok(entries) = fs::read_dir(directory) {
+      for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().map(|e| e == "ron").unwrap_or(false) {
+          paths.push(path);
+        }
+      }
+    }
+    paths.sort();
+    
+    let mut definitions = Vec::new();
+    for path in paths {
+      match ShipDefinition::load(&path) {
+        Ok(definition) => definitions.push(definition),
+        Err(e) => println!("Failed to load ship definition {}", e),
+      }
+    }
+    
+    definitions
+  }
+  
+  // The definition called name in resources/ships
+  pub fn named(name: &str) -> Option<ShipDefinition> {
+    ShipDefinition::load_all(SHIP_DIRECTORY).into_iter().find(|definition| definition.name == name)
+  }
+  
+  pub fn to_entity(&self, position: Vector2<f32>) -> BoxEntity {
+    Box::new(DefinedShip::new(self, position))
+  }
+}
+
+#[derive(Clone)]
+pub struct DefinedShip {
+  data: EntityData,
+  collision_scale: f32,
+}
+
+impl DefinedShip {
+  pub fn new(definition: &ShipDefinition, position: Vector2<f32>) -> DefinedShip {
+    let size = Vector2::new(definition.size.0, definition.size.1);
+    
+    let mut data = EntityData::new(position, size, definition.texture.to_string())
+                              .with_name(definition.name.to_string())
+                              .with_max_velocity(definition.max_velocity)
+                              .with_inertia(definition.inertia)
+                              .with_health(definition.health)
+                              .with_shield(definition.shield);
+    if let Some(delay) = definition.shield_delay {
+      data = data.with_shield_delay(delay);
+    }
+    if definition.shield_arcs {
+      data = data.with_shield_arcs();
+    }
+    
+    for section in &definition.sections {
+      data = data.with_ship_section(section.to_section());
+    }
+    
+    DefinedShip {
+      data,
+      collision_scale: definition.collision_scale,
+    }
+  }
+  
+  // For the ships the game can't go without, the player's Bulbz and the Brews levels are built
+  // from, so a missing file is a broken install rather than something to carry on from.
+  pub fn named(name: &str, position: Vector2<f32>) -> DefinedShip {
+    match ShipDefinition::named(name) {
+      Some(definition) => DefinedShip::new(&definition, position),
+      None => panic!("No ship called {} in {}", name, SHIP_DIRECTORY),
+    }
+  }
+  
+  pub fn with_health(mut self, health: f32) -> DefinedShip {
+    self.data = self.data.with_health(health);
+    self
+  }
+  
+  pub fn as_hostile(mut self) -> DefinedShip {
+    self.data = self.data.as_hostile();
+    self
+  }
+}
+
+impl Entity for DefinedShip {
+  fn data(&self) -> &EntityData {
+    &self.data
+  }
+  
+  fn mut_data(&mut self) -> &mut EntityData {
+    &mut self.data
+  }
+  
+  fn collision_information(&self) -> Vec<(Vector2<f32>, f32)> {
+    let radius = self.data().size.x.min(self.data().size.y)*0.5 * self.collision_scale;
+    
+    vec!((Vector2::new(0.0, 0.0), radius))
+  }
+  
+  fn collide_with(&mut self, entity: &mut BoxEntity) -> Option<f32> {
+    self.entity_collision(entity, true)
+  }
+}
@@ -3,6 +3,7 @@ pub use self::brew::Brew;
 pub use self::sun::Sun;
 pub use self::astroid::Astroid;
 pub use self::wall::Wall;
+pub use self::defined_ship::{DefinedShip, ShipDefinition, SectionDefinition, SlotDefinition, SectionKind, SHIP_DIRECTORY};
 
 pub mod sections;
 
@@ -11,15 +12,20 @@ mod brew;
 mod sun;
 mod astroid;
 mod wall;
+mod defined_ship;
 
 use maat_graphics::DrawCall;
 use maat_graphics::math;
 
-use crate::modules::entities::sections::{ShipSection, RepairBay, HullMaterial};
+use crate::modules::entities::sections::{ShipSection, ModularSlot, RepairBay};
 use crate::modules::projectiles::{Projectile, BoxProjectile};
-use crate::modules::buffs::{Buff, BoxBuff};
+use crate::modules::buffs::{BoxBuff, StatusKind};
 use crate::modules::controllers::{EntityController, BoxEntityController};
 use crate::modules::abilities::{BoxAbility, NoAbility};
+use crate::modules::colliders::{self, Collider};
+use crate::modules::gravity::GravityWell;
+use crate::modules::damage::{Damage, DamageType, Resistances};
+use crate::modules::stats::{Stat, Stats, StatModifier};
 
 use parking_lot::Mutex;
 
@@ -27,12 +33,34 @@ use std::f32::consts::PI;
 
 use crate::cgmath::{Vector2, Vector3, Vector4, InnerSpace};
 
+use serde::Deserialize;
+
 use std::sync::Arc;
 
+// Mass per square unit of size for entities that don't set their own
+const DEFAULT_DENSITY: f32 = 0.001;
+// Health lost per unit of impulse taken in a collision
+const COLLISION_DAMAGE: f32 = 0.0005;
+// Overlap left alone so resting contacts don't jitter, and how much of the rest is undone a tick
+const CORRECTION_SLOP: f32 = 0.5;
+const CORRECTION_PERCENT: f32 = 0.8;
+// Degrees per second for entities that don't set their own
+const DEFAULT_MAX_ANGULAR_VELOCITY: f32 = 540.0;
+// Energy pool, and what it regains each second without any reactors
+const DEFAULT_ENERGY: f32 = 100.0;
+const DEFAULT_ENERGY_REGEN: f32 = 5.0;
+// Energy spent on each point of shield regained
+const SHIELD_ENERGY_COST: f32 = 1.0;
+// Shields hold up well against energy weapons and poorly against solid rounds
+const SHIELD_ENERGY_RESISTANCE: f32 = 0.5;
+const SHIELD_KINETIC_RESISTANCE: f32 = -0.5;
+// Seconds after a hit before shields start regenerating
+const DEFAULT_SHIELD_DELAY: f32 = 2.0;
+
 pub type MutexEntity = Arc<Mutex<BoxEntity>>;
 pub type BoxEntity = Box<Entity + Send + Sync>;
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Deserialize)]
 pub enum Hostility {
   Hostile,
   Neutral,
@@ -85,6 +113,14 @@ impl Hostility {
     }
   }
   
+  // Sides that go looking for each other, misc and neutral entities only get hit on the way
+  pub fn is_enemy_of(&self, hostility: &Hostility) -> bool {
+    match (self, hostility) {
+      (Hostility::Hostile, Hostility::Friendly) | (Hostility::Friendly, Hostility::Hostile) => true,
+      _ => false,
+    }
+  }
+  
   pub fn check_can_hurt(&self, hostility: &Hostility) -> bool {
     if self.check_can_hit(hostility) {
       match *self {
@@ -123,6 +159,32 @@ impl Hostility {
   }
 }
 
+// Which side of a ship's shield a hit lands on, always Front for shields without arcs
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ShieldArc {
+  Front,
+  Rear,
+}
+
+// Every ship defined in resources/ships followed by the novelty picks, looked up by name when
+// a replay is loaded.
+pub fn selectable_ships() -> Vec<BoxEntity> {
+  let position = Vector2::new(0.0, 0.0);
+  let size = Vector2::new(300.0, 300.0);
+  
+  let mut ships: Vec<BoxEntity> = ShipDefinition::load_all(SHIP_DIRECTORY).iter()
+                                    .map(|definition| definition.to_entity(position))
+                                    .collect();
+  ships.insert(0, Box::new(Sun::new(position)));
+  ships.insert(0, Box::new(Astroid::new(position, size)));
+  
+  ships
+}
+
+pub fn selectable_ship(name: &str) -> Option<BoxEntity> {
+  selectable_ships().into_iter().find(|ship| ship.name() == name)
+}
+
 #[derive(Clone)]
 pub struct FullEntity {
   pub ai: BoxEntityController,
@@ -131,7 +193,9 @@ pub struct FullEntity {
 }
 
 impl FullEntity {
-  pub fn new(controller: Box<EntityController>, entity: BoxEntity) -> FullEntity { 
+  pub fn new(mut controller: Box<EntityController>, mut entity: BoxEntity) -> FullEntity { 
+    controller.take_control(&mut entity);
+    
     FullEntity {
       ai: controller,
       entity: Arc::new(Mutex::new(entity)),
@@ -146,87 +210,216 @@ unsafe impl Sync for EntityData {
 }
 
 
+// Shortest turn in degrees from one rotation to another, between -180 and 180
+pub fn angle_difference(from: f32, to: f32) -> f32 {
+  let difference = (to - from) % 360.0;
+  if difference > 180.0 {
+    difference - 360.0
+  } else if difference < -180.0 {
+    difference + 360.0
+  } else {
+    difference
+  }
+}
+
 #[derive(Clone)]
 pub struct EntityData {
   position: Vector2<f32>,
   rotation: f32,
   size: Vector2<f32>,
   texture: String,
+  // what ship select and replays know it by, its texture unless it is given one
+  name: String,
   velocity: Vector2<f32>,
-  max_velocity: f32,
   acceleration: Vector2<f32>,
   inertia: f32,
   health: f32,
-  health_regen: f32, // per second
   max_health: f32,
   shield: f32,
-  shield_regen: f32,
   max_shield: f32,
+  shield_resistances: Resistances,
+  // a broken shield absorbs nothing until it has fully recharged
+  shield_broken: bool,
+  // split into a front and rear arc, each holding up to max_shield
+  has_shield_arcs: bool,
+  rear_shield: f32,
+  rear_shield_broken: bool,
+  shield_delay: f32,
+  // seconds until the shields start regenerating again
+  shield_recharge_in: f32,
+  energy: f32,
+  max_energy: f32,
   phase_mode: bool,
+  // can't thrust, turn or use abilities
+  stunned: bool,
+  // can't fire weapons
+  disarmed: bool,
+  immunities: Vec<StatusKind>,
+  // kind and stacks of every status effect on it, kept up to date by whatever holds its buffs
+  statuses: Vec<(StatusKind, u32)>,
   projectiles: Vec<BoxProjectile>,
-  buffs: Vec<Box<Buff>>,
+  buffs: Vec<BoxBuff>,
   hostility: Hostility,
   should_exist: bool,
   ship_sections: Vec<Box<ShipSection>>,
-  hull_material: Box<ShipSection>,
+  // what its hull shrugs off when it has no hull material left, or never had any
+  resistances: Resistances,
   repair_bay: Box<ShipSection>,
+  id: u32,
+  last_attacker: Option<u32>,
+  // where physics last moved it from, None until it first moves or after a teleport
+  previous_position: Option<Vector2<f32>>,
+  // infinite for things collisions can't move
+  mass: f32,
+  restitution: f32,
+  friction: f32,
+  // scales the damage it does to whatever it collides with
+  impact_damage: f32,
+  // degrees per second
+  angular_velocity: f32,
+  // worked out from mass and size unless set
+  moment_of_inertia: Option<f32>,
+  // rotation the thrusters are turning it towards
+  heading: Option<f32>,
+  gravity_well: Option<GravityWell>,
+  // stats as set, and with the modifiers from its buffs applied
+  base_stats: Stats,
+  stats: Stats,
+  stat_modifiers: Vec<StatModifier>,
+  // pull from other wells this tick, used up by physics
+  gravity: Vector2<f32>,
 }
 
 impl EntityData {
   pub fn new_empty() -> EntityData {
+    let stats = Stats {
+      max_velocity: 1.0,
+      max_angular_velocity: DEFAULT_MAX_ANGULAR_VELOCITY,
+      health_regen: 0.0,
+      shield_regen: 0.0,
+      energy_regen: DEFAULT_ENERGY_REGEN,
+    };
+    
      EntityData {
       position: Vector2::new(0.0, 0.0),
       rotation: 0.0,
       size: Vector2::new(1.0, 1.0),
       texture: "".to_string(),
+      name: "".to_string(),
       velocity: Vector2::new(0.0, 0.0),
-      max_velocity: 1.0,
       acceleration: Vector2::new(0.0, 0.0),
       inertia: 0.33,
       health: 100.0,
-      health_regen: 0.0,
       max_health: 100.0,
       shield: 0.0,
-      shield_regen: 0.0,
       max_shield: 0.0,
+      shield_resistances: Resistances::new().with_resistance(DamageType::Energy, SHIELD_ENERGY_RESISTANCE)
+                                            .with_resistance(DamageType::Kinetic, SHIELD_KINETIC_RESISTANCE),
+      shield_broken: false,
+      has_shield_arcs: false,
+      rear_shield: 0.0,
+      rear_shield_broken: false,
+      shield_delay: DEFAULT_SHIELD_DELAY,
+      shield_recharge_in: 0.0,
+      energy: DEFAULT_ENERGY,
+      max_energy: DEFAULT_ENERGY,
       phase_mode: false,
+      stunned: false,
+      disarmed: false,
+      immunities: Vec::new(),
+      statuses: Vec::new(),
       projectiles: Vec::new(),
       buffs: Vec::new(),
       hostility: Hostility::Friendly,
       should_exist: true,
       ship_sections: Vec::new(),
-      hull_material: Box::new(HullMaterial::new(Vector2::new(0.0, 0.0), Vector2::new(50.0, 50.0))),
+      resistances: Resistances::new(),
       repair_bay: Box::new(RepairBay::new(Vector2::new(0.0, 0.0), Vector2::new(50.0, 50.0))),
+      id: 0,
+      last_attacker: None,
+      previous_position: None,
+      mass: DEFAULT_DENSITY,
+      restitution: 0.5,
+      friction: 0.3,
+      impact_damage: 1.0,
+      angular_velocity: 0.0,
+      moment_of_inertia: None,
+      heading: None,
+      gravity_well: None,
+      gravity: Vector2::new(0.0, 0.0),
+      base_stats: stats,
+      stats,
+      stat_modifiers: Vec::new(),
     }
   }
   
   pub fn new(position: Vector2<f32>, size: Vector2<f32>, texture: String) -> EntityData {
+    let stats = Stats {
+      max_velocity: 500.0,
+      max_angular_velocity: DEFAULT_MAX_ANGULAR_VELOCITY,
+      health_regen: 0.0,
+      shield_regen: 0.0,
+      energy_regen: DEFAULT_ENERGY_REGEN,
+    };
+    
      EntityData {
       position,
       rotation: 0.0,
       size,
       texture: texture.to_string(),
+      name: texture.to_string(),
       velocity: Vector2::new(0.0, 0.0),
-      max_velocity: 500.0,
       acceleration: Vector2::new(0.0, 0.0),
       inertia: 0.33,
       health: 100.0,
-      health_regen: 0.0,
       max_health: 100.0,
       shield: 0.0,
-      shield_regen: 0.0,
       max_shield: 0.0,
+      shield_resistances: Resistances::new().with_resistance(DamageType::Energy, SHIELD_ENERGY_RESISTANCE)
+                                            .with_resistance(DamageType::Kinetic, SHIELD_KINETIC_RESISTANCE),
+      shield_broken: false,
+      has_shield_arcs: false,
+      rear_shield: 0.0,
+      rear_shield_broken: false,
+      shield_delay: DEFAULT_SHIELD_DELAY,
+      shield_recharge_in: 0.0,
+      energy: DEFAULT_ENERGY,
+      max_energy: DEFAULT_ENERGY,
       phase_mode: false,
+      stunned: false,
+      disarmed: false,
+      immunities: Vec::new(),
+      statuses: Vec::new(),
       projectiles: Vec::new(),
       buffs: Vec::new(),
       hostility: Hostility::Friendly,
       should_exist: true,
       ship_sections: Vec::new(),
-      hull_material: Box::new(HullMaterial::new(Vector2::new(0.0, 0.0), Vector2::new(50.0, 50.0))),
+      resistances: Resistances::new(),
       repair_bay: Box::new(RepairBay::new(Vector2::new(0.0, 0.0), Vector2::new(50.0, 50.0))),
+      id: 0,
+      last_attacker: None,
+      previous_position: None,
+      mass: size.x*size.y*DEFAULT_DENSITY,
+      restitution: 0.5,
+      friction: 0.3,
+      impact_damage: 1.0,
+      angular_velocity: 0.0,
+      moment_of_inertia: None,
+      heading: None,
+      gravity_well: None,
+      gravity: Vector2::new(0.0, 0.0),
+      base_stats: stats,
+      stats,
+      stat_modifiers: Vec::new(),
     }
   }
   
+  pub fn with_name(mut self, name: String) -> EntityData {
+    self.name = name;
+    self
+  }
+  
   pub fn with_rotation(mut self, rot: f32) -> EntityData {
     self.rotation = rot;
     self
@@ -238,7 +431,7 @@ impl EntityData {
   }
   
   pub fn with_max_velocity(mut self, max_vel: f32) -> EntityData {
-    self.max_velocity = max_vel;
+    self.set_base_stat(Stat::MaxVelocity, max_vel);
     self
   }
   
@@ -247,6 +440,46 @@ impl EntityData {
     self
   }
   
+  pub fn with_mass(mut self, mass: f32) -> EntityData {
+    self.mass = mass;
+    self
+  }
+  
+  pub fn immovable(mut self) -> EntityData {
+    self.mass = std::f32::INFINITY;
+    self
+  }
+  
+  pub fn with_moment_of_inertia(mut self, moment_of_inertia: f32) -> EntityData {
+    self.moment_of_inertia = Some(moment_of_inertia);
+    self
+  }
+  
+  pub fn with_max_angular_velocity(mut self, max_angular_velocity: f32) -> EntityData {
+    self.set_base_stat(Stat::MaxAngularVelocity, max_angular_velocity);
+    self
+  }
+  
+  pub fn with_gravity_well(mut self, well: GravityWell) -> EntityData {
+    self.gravity_well = Some(well);
+    self
+  }
+  
+  pub fn with_restitution(mut self, restitution: f32) -> EntityData {
+    self.restitution = restitution;
+    self
+  }
+  
+  pub fn with_friction(mut self, friction: f32) -> EntityData {
+    self.friction = friction;
+    self
+  }
+  
+  pub fn with_impact_damage(mut self, impact_damage: f32) -> EntityData {
+    self.impact_damage = impact_damage;
+    self
+  }
+  
   pub fn with_health(mut self, health: f32) -> EntityData {
     self.health = health;
     self.max_health = health;
@@ -255,10 +488,31 @@ impl EntityData {
   
   pub fn with_shield(mut self, shield: f32) -> EntityData {
     self.shield = shield;
+    self.rear_shield = shield;
     self.max_shield = shield;
     self
   }
   
+  pub fn with_immunity(mut self, kind: StatusKind) -> EntityData {
+    self.immunities.push(kind);
+    self
+  }
+  
+  pub fn with_shield_arcs(mut self) -> EntityData {
+    self.has_shield_arcs = true;
+    self
+  }
+  
+  pub fn with_shield_delay(mut self, delay: f32) -> EntityData {
+    self.shield_delay = delay;
+    self
+  }
+  
+  pub fn with_resistances(mut self, resistances: Resistances) -> EntityData {
+    self.resistances = resistances;
+    self
+  }
+  
   pub fn as_hostile(mut self) -> EntityData {
     self.hostility.make_hostile();
     self
@@ -279,15 +533,39 @@ impl EntityData {
     self
   }
   
+  // Adds the slot to the last section added
+  pub fn with_modular_slot(mut self, slot: ModularSlot) -> EntityData {
+    if let Some(section) = self.ship_sections.last_mut() {
+      section.add_modular_slot(slot);
+    }
+    self
+  }
+  
   pub fn with_health_regen(mut self, regen: f32) -> EntityData {
-    self.health_regen = regen;
+    self.set_base_stat(Stat::HealthRegen, regen);
     self
   }
   
   pub fn with_shield_regen(mut self, regen: f32) -> EntityData {
-    self.shield_regen = regen;
+    self.set_base_stat(Stat::ShieldRegen, regen);
+    self
+  }
+  
+  pub fn with_energy(mut self, energy: f32) -> EntityData {
+    self.energy = energy;
+    self.max_energy = energy;
     self
   }
+  
+  pub fn with_energy_regen(mut self, regen: f32) -> EntityData {
+    self.set_base_stat(Stat::EnergyRegen, regen);
+    self
+  }
+  
+  fn set_base_stat(&mut self, stat: Stat, value: f32) {
+    self.base_stats.set(stat, value);
+    self.stats = self.base_stats.modified(&self.stat_modifiers);
+  }
 }
 
 pub trait EntityClone {
@@ -311,33 +589,72 @@ pub trait Entity: EntityClone {
   fn mut_data(&mut self) -> &mut EntityData;
   
   fn collision_information(&self) -> Vec<(Vector2<f32>, f32)>;
-  fn collide_with(&mut self, entity: &mut BoxEntity);
+  // Returns the time of impact, see entity_collision
+  fn collide_with(&mut self, entity: &mut BoxEntity) -> Option<f32>;
   
   fn update(&mut self, delta_time: f32) -> (Vec<BoxBuff>, Vec<BoxProjectile>) {
+    let generation = self.energy_generation();
+    self.mut_data().energy = (self.data().energy + generation*delta_time).min(self.data().max_energy);
+    
     self.physics(delta_time);
-    self.mut_data().health += self.data().health_regen*delta_time;
-    self.mut_data().shield += self.data().shield_regen*delta_time;
+    self.mut_data().health += self.data().stats.health_regen*delta_time;
+    
+    // shields wait out the delay since the last hit, then only regenerate as far as there is
+    // energy to pay for them
+    let recharge_in = (self.data().shield_recharge_in - delta_time).max(0.0);
+    self.mut_data().shield_recharge_in = recharge_in;
+    let max_shield = self.data().max_shield;
+    for arc in self.shield_arcs() {
+      let (mut shield, mut broken) = self.shield_arc(arc);
+      let shield_regen = (self.data().stats.shield_regen*delta_time).min((max_shield - shield).max(0.0));
+      if recharge_in <= 0.0 && shield_regen > 0.0 {
+        let paid = self.drain_energy(shield_regen*SHIELD_ENERGY_COST);
+        shield += paid/SHIELD_ENERGY_COST;
+      }
+      if shield >= max_shield {
+        shield = max_shield;
+        broken = false;
+      }
+      self.set_shield_arc(arc, shield.max(0.0), broken);
+    }
     if self.data().health >= self.data().max_health {
       self.mut_data().health = self.data().max_health;
     }
-    if self.data().shield >= self.data().max_shield {
-      self.mut_data().shield = self.data().max_shield;
-    }
-    if self.data().shield < 0.0 {
-      self.mut_data().shield = 0.0;
-    }
     
     (self.return_buffs(), self.return_projectiles())
   }
   
+  // Assigned by the World, 0 until the entity is added to one
+  fn id(&self) -> u32 {
+    self.data().id
+  }
+  
+  // Id of whatever's projectile last hurt this entity
+  fn last_attacker(&self) -> Option<u32> {
+    self.data().last_attacker
+  }
+  
   fn texture(&self) -> String {
     self.data().texture.to_string()
   }
   
+  fn name(&self) -> String {
+    self.data().name.to_string()
+  }
+  
   fn position(&self) -> Vector2<f32> {
     self.data().position
   }
   
+  fn previous_position(&self) -> Vector2<f32> {
+    self.data().previous_position.unwrap_or(self.data().position)
+  }
+  
+  // How far it moved during the last physics step
+  fn motion(&self) -> Vector2<f32> {
+    self.data().position - self.previous_position()
+  }
+  
   fn size(&self) -> Vector2<f32> {
     self.data().size
   }
@@ -355,7 +672,194 @@ pub trait Entity: EntityClone {
   }
   
   fn max_velocity(&self) -> f32 {
-    self.data().max_velocity
+    self.data().stats.max_velocity
+  }
+  
+  // Stats as set, before any buffs
+  fn base_stats(&self) -> Stats {
+    self.data().base_stats
+  }
+  
+  // Stats with its buffs applied, what it actually moves and regenerates at
+  fn stats(&self) -> Stats {
+    self.data().stats
+  }
+  
+  fn mass(&self) -> f32 {
+    self.data().mass
+  }
+  
+  // 0 for immovable entities
+  fn inverse_mass(&self) -> f32 {
+    1.0 / self.data().mass
+  }
+  
+  // A solid rectangle of its size unless set
+  fn moment_of_inertia(&self) -> f32 {
+    let size = self.data().size;
+    self.data().moment_of_inertia.unwrap_or(self.data().mass*(size.x*size.x + size.y*size.y)/12.0)
+  }
+  
+  fn angular_velocity(&self) -> f32 {
+    self.data().angular_velocity
+  }
+  
+  fn heading(&self) -> Option<f32> {
+    self.data().heading
+  }
+  
+  fn gravity_well(&self) -> Option<GravityWell> {
+    self.data().gravity_well
+  }
+  
+  // Adds to the pull physics applies next, immovable entities ignore it
+  fn pull(&mut self, acceleration: Vector2<f32>) {
+    if self.inverse_mass() > 0.0 {
+      self.mut_data().gravity += acceleration;
+    }
+  }
+  
+  // Most torque its sections can turn it with
+  fn turn_torque(&self) -> f32 {
+    let (centre, scale) = self.section_layout();
+    self.data().ship_sections.iter()
+                             .filter(|section| !section.is_destroyed())
+                             .map(|section| section.torque(centre, scale)).sum()
+  }
+  
+  // Share of its thrust it still has, so losing thrusters cuts acceleration. 1 without any.
+  fn thrust_fraction(&self) -> f32 {
+    let sections = &self.data().ship_sections;
+    let total: f32 = sections.iter().map(|section| section.thrust()).sum();
+    if total <= 0.0 {
+      return 1.0;
+    }
+    
+    let remaining: f32 = sections.iter()
+                                 .filter(|section| !section.is_destroyed())
+                                 .map(|section| section.thrust()).sum();
+    remaining/total
+  }
+  
+  fn energy(&self) -> f32 {
+    self.data().energy
+  }
+  
+  fn max_energy(&self) -> f32 {
+    self.data().max_energy
+  }
+  
+  // Energy regained per second, its own regen plus whatever its working reactors generate
+  fn energy_generation(&self) -> f32 {
+    self.data().stats.energy_regen + self.data().ship_sections.iter()
+                                                   .filter(|section| !section.is_destroyed())
+                                                   .map(|section| section.generation()).sum::<f32>()
+  }
+  
+  // Energy per second its working thrusters draw at full thrust
+  fn power_draw(&self) -> f32 {
+    self.data().ship_sections.iter()
+                             .filter(|section| !section.is_destroyed())
+                             .map(|section| section.power_draw()).sum()
+  }
+  
+  // Spends amount if it has that much, false and nothing spent otherwise
+  fn use_energy(&mut self, amount: f32) -> bool {
+    if amount > self.data().energy {
+      return false;
+    }
+    
+    self.mut_data().energy -= amount;
+    true
+  }
+  
+  // Spends up to amount, returning how much it could
+  fn drain_energy(&mut self, amount: f32) -> f32 {
+    let drained = amount.min(self.data().energy).max(0.0);
+    self.mut_data().energy -= drained;
+    drained
+  }
+  
+  fn health(&self) -> f32 {
+    self.data().health
+  }
+  
+  fn max_health(&self) -> f32 {
+    self.data().max_health
+  }
+  
+  // Strength of the front arc, or of the whole shield without arcs
+  fn shield(&self) -> f32 {
+    self.data().shield
+  }
+  
+  fn rear_shield(&self) -> f32 {
+    self.shield_arc(ShieldArc::Rear).0
+  }
+  
+  fn is_shield_broken(&self) -> bool {
+    self.data().shield_broken
+  }
+  
+  fn shield_arcs(&self) -> Vec<ShieldArc> {
+    if self.data().has_shield_arcs {
+      vec!(ShieldArc::Front, ShieldArc::Rear)
+    } else {
+      vec!(ShieldArc::Front)
+    }
+  }
+  
+  // Strength and whether it's broken
+  fn shield_arc(&self, arc: ShieldArc) -> (f32, bool) {
+    match arc {
+      ShieldArc::Rear if self.data().has_shield_arcs => (self.data().rear_shield, self.data().rear_shield_broken),
+      _ => (self.data().shield, self.data().shield_broken),
+    }
+  }
+  
+  fn set_shield_arc(&mut self, arc: ShieldArc, shield: f32, broken: bool) {
+    match arc {
+      ShieldArc::Rear if self.data().has_shield_arcs => {
+        self.mut_data().rear_shield = shield;
+        self.mut_data().rear_shield_broken = broken;
+      },
+      _ => {
+        self.mut_data().shield = shield;
+        self.mut_data().shield_broken = broken;
+      },
+    }
+  }
+  
+  // The arc facing point, from the angle of the impact against the way the ship is facing
+  fn shield_arc_at(&self, point: Vector2<f32>) -> ShieldArc {
+    if !self.data().has_shield_arcs {
+      return ShieldArc::Front;
+    }
+    
+    let rotation = self.data().rotation.to_radians();
+    let facing = Vector2::new(-rotation.sin(), rotation.cos());
+    if (point - self.data().position).dot(facing) >= 0.0 {
+      ShieldArc::Front
+    } else {
+      ShieldArc::Rear
+    }
+  }
+  
+  fn max_shield(&self) -> f32 {
+    self.data().max_shield
+  }
+  
+  fn shield_resistances(&self) -> Resistances {
+    self.data().shield_resistances
+  }
+  
+  // From its first hull material section still standing, otherwise its own
+  fn hull_resistances(&self) -> Resistances {
+    self.data().ship_sections.iter()
+                             .filter(|section| !section.is_destroyed())
+                             .filter_map(|section| section.resistances())
+                             .next()
+                             .unwrap_or(self.data().resistances)
   }
   
   fn should_exist(&self) -> bool {
@@ -366,10 +870,71 @@ pub trait Entity: EntityClone {
     self.data().phase_mode
   }
   
+  fn is_stunned(&self) -> bool {
+    self.data().stunned
+  }
+  
+  fn is_disarmed(&self) -> bool {
+    self.data().disarmed
+  }
+  
+  fn is_immune(&self, kind: StatusKind) -> bool {
+    self.data().immunities.contains(&kind)
+  }
+  
+  fn statuses(&self) -> Vec<(StatusKind, u32)> {
+    self.data().statuses.clone()
+  }
+  
   fn ship_sections(&self) -> &Vec<Box<ShipSection>> {
     &self.data().ship_sections
   }
   
+  // Health per second the ship's working repair bays give back, nothing without one
+  fn repair_rate(&self) -> f32 {
+    self.data().ship_sections.iter()
+                             .filter(|section| !section.is_destroyed())
+                             .map(|section| section.repair_rate()).sum()
+  }
+  
+  // Every slot on every section, by id
+  fn modular_slots(&self) -> Vec<ModularSlot> {
+    let mut slots: Vec<ModularSlot> = self.data().ship_sections.iter().flat_map(|section| section.modular_slots()).collect();
+    slots.sort_by_key(|slot| slot.id());
+    slots
+  }
+  
+  // Slots holding an ability on sections that haven't been destroyed, by id
+  fn ability_slots(&self) -> Vec<ModularSlot> {
+    let mut slots: Vec<ModularSlot> = self.data().ship_sections.iter()
+                                          .filter(|section| !section.is_destroyed())
+                                          .flat_map(|section| section.modular_slots())
+                                          .filter(|slot| slot.has_ability())
+                                          .collect();
+    slots.sort_by_key(|slot| slot.id());
+    slots
+  }
+  
+  fn install_ability(&mut self, slot_id: i32, ability: BoxAbility) -> bool {
+    for section in &mut self.mut_data().ship_sections {
+      if section.install_ability(slot_id, ability.clone()) {
+        return true;
+      }
+    }
+    
+    false
+  }
+  
+  fn remove_ability(&mut self, slot_id: i32) -> Option<BoxAbility> {
+    for section in &mut self.mut_data().ship_sections {
+      if let Some(ability) = section.remove_ability(slot_id) {
+        return Some(ability);
+      }
+    }
+    
+    None
+  }
+  
   fn collision_circles(&self) -> Vec<Vector3<f32>> {
     let information = self.collision_information();
     
@@ -386,35 +951,175 @@ pub trait Entity: EntityClone {
     entity_circles
   }
   
+  // What it collides as, the collision circles unless it declares a better fitting shape
+  fn colliders(&self) -> Vec<Collider> {
+    self.collision_circles().iter().map(|circle| Collider::circle(circle.truncate(), circle.z)).collect()
+  }
+  
+  // Section offsets and sizes are in their own units, the ones the module viewer lays them out
+  // in, so the layout is centred and scaled to fit over the entity
+  fn section_layout(&self) -> (Vector2<f32>, f32) {
+    let sections = &self.data().ship_sections;
+    if sections.is_empty() {
+      return (Vector2::new(0.0, 0.0), 1.0);
+    }
+    
+    let mut min = Vector2::new(std::f32::MAX, std::f32::MAX);
+    let mut max = Vector2::new(std::f32::MIN, std::f32::MIN);
+    for section in sections {
+      let half_size = section.size()*0.5;
+      min.x = min.x.min(section.offset().x-half_size.x);
+      min.y = min.y.min(section.offset().y-half_size.y);
+      max.x = max.x.max(section.offset().x+half_size.x);
+      max.y = max.y.max(section.offset().y+half_size.y);
+    }
+    
+    let extent = max-min;
+    let size = self.data().size;
+    let scale = (size.x/extent.x.max(1.0)).min(size.y/extent.y.max(1.0));
+    
+    ((min+max)*0.5, scale)
+  }
+  
+  // One per ship section, in the same order
+  fn section_colliders(&self) -> Vec<Collider> {
+    let (centre, scale) = self.section_layout();
+    let position = self.data().position;
+    let rotation = self.data().rotation;
+    
+    self.data().ship_sections.iter().map(|section| section.collider(position, rotation, centre, scale)).collect()
+  }
+  
+  // The section under point, or the closest one if the point misses them all
+  fn section_at(&self, point: Vector2<f32>) -> Option<usize> {
+    let colliders = self.section_colliders();
+    if let Some(i) = colliders.iter().position(|collider| collider.contains_point(point)) {
+      return Some(i);
+    }
+    
+    let mut closest: Option<(usize, f32)> = None;
+    for (i, collider) in colliders.iter().enumerate() {
+      let (min, max) = collider.bounds();
+      let distance = ((min+max)*0.5 - point).magnitude2();
+      if closest.map(|(_, d)| distance < d).unwrap_or(true) {
+        closest = Some((i, distance));
+      }
+    }
+    
+    closest.map(|(i, _)| i)
+  }
+  
   fn gain_shield(&mut self, shield_value: f32) {
-    self.mut_data().shield += shield_value;
+    for arc in self.shield_arcs() {
+      let (shield, broken) = self.shield_arc(arc);
+      self.set_shield_arc(arc, shield + shield_value, broken);
+    }
+  }
+  
+  fn repair(&mut self, health_value: f32) {
+    self.mut_data().health = (self.data().health + health_value).min(self.data().max_health);
+  }
+  
+  fn attacked_by(&mut self, id: u32) {
+    if id != 0 && id != self.data().id {
+      self.mut_data().last_attacker = Some(id);
+    }
   }
   
-  fn hit(&mut self, damage: f32) {
-    if self.data().shield > 0.0 {
-      if self.data().shield < damage {
-        self.mut_data().shield -= damage;
-        self.mut_data().health += self.data().shield;
-        self.mut_data().shield = 0.0;
+  // A hit on the front arc, or on the whole shield without arcs
+  fn hit(&mut self, damage: Damage) -> f32 {
+    self.hit_arc(damage, ShieldArc::Front)
+  }
+  
+  // The shield arc soaks up what it can at its own resistances, breaking if that empties it,
+  // and the share of the hit it couldn't lands on the hull at the hull material's. Any hit holds
+  // off shield regen for the shield delay. Returns the health lost.
+  fn hit_arc(&mut self, damage: Damage, arc: ShieldArc) -> f32 {
+    if let Some(id) = damage.source() {
+      self.attacked_by(id);
+    }
+    
+    if damage.amount() > 0.0 {
+      self.mut_data().shield_recharge_in = self.data().shield_delay;
+    }
+    
+    let mut remaining = damage.amount();
+    let (shield, broken) = self.shield_arc(arc);
+    if shield > 0.0 && !broken {
+      let against_shield = self.data().shield_resistances.reduce(&damage);
+      if against_shield < shield {
+        self.set_shield_arc(arc, shield - against_shield, false);
+        remaining = 0.0;
       } else {
-        self.mut_data().shield -= damage;
+        remaining *= 1.0 - shield/against_shield;
+        self.set_shield_arc(arc, 0.0, true);
       }
-    } else {
-      self.mut_data().health -= damage;
     }
     
+    let taken = self.hull_resistances().reduce(&damage.with_amount(remaining));
+    self.mut_data().health -= taken;
+    
     if self.data().health <= 0.0 {
       self.mut_data().should_exist = false;
       self.mut_data().health = 0.0;
     }
+    
+    taken
+  }
+  
+  // Like hit, but whatever gets through the shield also lands on the section under point.
+  // Destroying a vital section destroys the entity with it.
+  fn hit_at(&mut self, damage: Damage, point: Vector2<f32>) {
+    let arc = self.shield_arc_at(point);
+    let through_shield = self.hit_arc(damage, arc);
+    
+    if through_shield <= 0.0 {
+      return;
+    }
+    
+    if let Some(i) = self.section_at(point) {
+      let section = &mut self.mut_data().ship_sections[i];
+      if section.damage(through_shield) && section.is_vital() {
+        self.mut_data().should_exist = false;
+        self.mut_data().health = 0.0;
+      }
+    }
   }
   
   fn set_phase_mode(&mut self, should_phase: bool) {
     self.mut_data().phase_mode = should_phase;
   }
   
+  fn set_stunned(&mut self, stunned: bool) {
+    self.mut_data().stunned = stunned;
+  }
+  
+  fn set_disarmed(&mut self, disarmed: bool) {
+    self.mut_data().disarmed = disarmed;
+  }
+  
+  fn add_immunity(&mut self, kind: StatusKind) {
+    if !self.is_immune(kind) {
+      self.mut_data().immunities.push(kind);
+    }
+  }
+  
+  fn set_statuses(&mut self, statuses: Vec<(StatusKind, u32)>) {
+    self.mut_data().statuses = statuses;
+  }
+  
+  // Teleports, so the next collision pass doesn't sweep the jump
   fn set_position(&mut self, position: Vector2<f32>) {
     self.mut_data().position = position;
+    self.mut_data().previous_position = None;
+  }
+  
+  fn set_id(&mut self, id: u32) {
+    self.mut_data().id = id;
+  }
+  
+  fn set_hostility(&mut self, hostility: Hostility) {
+    self.mut_data().hostility = hostility;
   }
   
   fn set_velocity(&mut self, vel: Vector2<f32>) {
@@ -422,7 +1127,7 @@ pub trait Entity: EntityClone {
   }
   
   fn set_max_velocty(&mut self, max_vel: f32) {
-    self.mut_data().max_velocity = max_vel;
+    self.mut_data().set_base_stat(Stat::MaxVelocity, max_vel);
   }
   
   fn set_rotation(&mut self, rot: f32) {
@@ -434,17 +1139,48 @@ pub trait Entity: EntityClone {
   }
   
   fn set_shield_regen(&mut self, shield_regen: f32) {
-    self.mut_data().shield_regen = shield_regen;
+    self.mut_data().set_base_stat(Stat::ShieldRegen, shield_regen);
   }
   
-  fn set_facing(&mut self, target: Vector2<f32>) {
+  // Replaces the modifiers on its stats and works its effective stats out again from the base
+  fn set_stat_modifiers(&mut self, modifiers: Vec<StatModifier>) {
+    self.mut_data().stat_modifiers = modifiers;
+    let stats = self.data().base_stats.modified(&self.data().stat_modifiers);
+    self.mut_data().stats = stats;
+  }
+  
+  fn set_shield_delay(&mut self, delay: f32) {
+    self.mut_data().shield_delay = delay;
+  }
+  
+  fn set_shield_arcs(&mut self, has_shield_arcs: bool) {
+    self.mut_data().has_shield_arcs = has_shield_arcs;
+  }
+  
+  // The rotation that faces target
+  fn heading_to(&self, target: Vector2<f32>) -> f32 {
     let direction = target-self.data().position;
     let rotation = direction.x.atan2(direction.y);
     
-    let rot_degree = 360.0-(rotation*180.0)/PI;
+    360.0-(rotation*180.0)/PI
+  }
+  
+  // Snaps to face target, see steer_towards for turning there under thrust
+  fn set_facing(&mut self, target: Vector2<f32>) {
+    let rot_degree = self.heading_to(target);
     self.mut_data().rotation = rot_degree;
   }
   
+  // Turns towards heading as fast as its thrusters allow, or snaps to it without any
+  fn set_heading(&mut self, heading: f32) {
+    self.mut_data().heading = Some(heading);
+  }
+  
+  fn steer_towards(&mut self, target: Vector2<f32>) {
+    let heading = self.heading_to(target);
+    self.set_heading(heading);
+  }
+  
   fn set_velocity_magnitude(&mut self, vel: f32) {
     let vel_dir = math::normalise_vector2(self.data().velocity);
     self.mut_data().velocity = vel_dir*vel;
@@ -469,60 +1205,164 @@ pub trait Entity: EntityClone {
     } else if self.data().hostility.is_neutral() {
       projectile.make_neutral();
     }
+    projectile.set_owner(self.data().id);
     
     self.mut_data().projectiles.push(projectile);
   }
   
-  fn activate_buff(&mut self, buff: Box<Buff>) {
+  fn activate_buff(&mut self, buff: BoxBuff) {
     self.mut_data().buffs.push(buff);
   }
   
   fn physics(&mut self, delta_time: f32) {
     let velocity = self.data().velocity;
-    let max_velocity = self.data().max_velocity;
-    let acceleration = self.data().acceleration;
+    let max_velocity = self.data().stats.max_velocity;
+    // a stunned entity's thrusters are out
+    let acceleration = if self.data().stunned { Vector2::new(0.0, 0.0) } else { self.data().acceleration };
     let inertia = self.data().inertia;
+    self.mut_data().previous_position = Some(self.data().position);
     self.mut_data().position += velocity*delta_time;
     self.mut_data().velocity -= velocity*(1.0-inertia)*delta_time;
-    self.mut_data().velocity += acceleration*max_velocity*(1.0-inertia)*delta_time;
-    
-    if self.data().velocity.magnitude() > self.data().max_velocity {
-      self.mut_data().velocity = math::normalise_vector2(velocity)*max_velocity;
+    let mut thrust_fraction = self.thrust_fraction();
+    let power = self.power_draw()*acceleration.magnitude().min(1.0)*delta_time;
+    if power > 0.0 {
+      thrust_fraction *= self.drain_energy(power)/power;
+    }
+    // thrust can't take it past max velocity, though it keeps any speed it already had over
+    // it, from gravity or a collision, and can still steer with it
+    let coasting_speed = self.data().velocity.magnitude();
+    self.mut_data().velocity += acceleration*max_velocity*(1.0-inertia)*thrust_fraction*delta_time;
+    let speed_limit = coasting_speed.max(max_velocity);
+    if self.data().velocity.magnitude() > speed_limit {
+      let direction = math::normalise_vector2(self.data().velocity);
+      self.mut_data().velocity = direction*speed_limit;
     }
     
+    // gravity isn't capped, a well can pull an entity along faster than it can fly
+    let gravity = self.data().gravity;
+    self.mut_data().velocity += gravity*delta_time;
+    self.mut_data().gravity = Vector2::new(0.0, 0.0);
+    
     self.mut_data().acceleration = Vector2::new(0.0, 0.0);
+    
+    if self.data().stunned {
+      let angular_velocity = self.data().angular_velocity;
+      self.mut_data().rotation += angular_velocity*delta_time;
+    } else {
+      self.turn(delta_time);
+    }
+  }
+  
+  // Spins up towards the heading and slows in time to stop on it, limited by the torque its
+  // thrusters give against its moment of inertia. With no heading the thrusters hold it still.
+  fn turn(&mut self, delta_time: f32) {
+    let torque = self.turn_torque();
+    if torque <= 0.0 {
+      if let Some(heading) = self.data().heading {
+        self.mut_data().rotation = heading;
+        self.mut_data().angular_velocity = 0.0;
+      }
+      
+      let angular_velocity = self.data().angular_velocity;
+      self.mut_data().rotation += angular_velocity*delta_time;
+      return;
+    }
+    
+    let angular_acceleration = (torque / self.moment_of_inertia()).to_degrees();
+    let max_angular_velocity = self.data().stats.max_angular_velocity;
+    let angular_velocity = self.data().angular_velocity;
+    
+    let target_velocity = match self.data().heading {
+      Some(heading) => {
+        let difference = angle_difference(self.data().rotation, heading);
+        if difference.abs() < 0.01 && angular_velocity.abs() <= angular_acceleration*delta_time {
+          self.mut_data().rotation = heading;
+          self.mut_data().angular_velocity = 0.0;
+          return;
+        }
+        
+        // fastest it can go and still stop in time
+        difference.signum()*(2.0*angular_acceleration*difference.abs()).sqrt().min(max_angular_velocity)
+      },
+      None => 0.0,
+    };
+    
+    let change = (target_velocity - angular_velocity).max(-angular_acceleration*delta_time)
+                                                      .min(angular_acceleration*delta_time);
+    self.mut_data().angular_velocity += change;
+    let angular_velocity = self.data().angular_velocity;
+    self.mut_data().rotation += angular_velocity*delta_time;
   }
   
-  fn entity_collision(&mut self, entity: &mut BoxEntity, damage: f32, velocity: f32) {
-    let entity_circles = entity.collision_circles();
-    let astroid_circles = self.collision_circles();
-    
-    let mut collided = false;
-    
-    for e_circle in entity_circles {
-      for p_circle in &astroid_circles {
-        if math::circle_collision(e_circle, *p_circle) {
-          entity.hit(damage);
-          self.hit(damage);
-          
-          let center = (self.position()+entity.position())*0.5;
-          
-          let astroid_direction = math::normalise_vector2(self.position()-center);
-          let _current_vel = self.velocity();
-          self.set_velocity(astroid_direction*velocity);
-          self.apply_acceleration_in_direction(astroid_direction);
-          
-          let entity_direction =  -1.0*astroid_direction;
-          let _current_vel = entity.velocity();
-          entity.set_velocity(entity_direction*velocity);
-          entity.apply_acceleration_in_direction(entity_direction);
-          
-          collided = true;
-          break;
+  // Sweeps both entities from their previous positions and, if they touch, puts them back where
+  // they met. They then bounce off each other by impulse, slide against each other with
+  // friction and are pushed apart if still overlapping. Only harmful collisions do damage, in
+  // proportion to the impulse. Returns the time of impact, as a fraction of this tick's motion.
+  fn entity_collision(&mut self, entity: &mut BoxEntity, harmful: bool) -> Option<f32> {
+    let time = colliders::time_of_impact(&self.colliders(), self.motion(),
+                                         &entity.colliders(), entity.motion());
+    
+    if let Some(time) = time {
+      if time > 0.0 {
+        let position = self.previous_position() + self.motion()*time;
+        let entity_position = entity.previous_position() + entity.motion()*time;
+        self.set_position(position);
+        entity.set_position(entity_position);
+      }
+      
+      let contact = match colliders::contact(&self.colliders(), &entity.colliders()) {
+        Some(contact) => contact,
+        None => return Some(time),
+      };
+      
+      let inverse_mass = self.inverse_mass();
+      let entity_inverse_mass = entity.inverse_mass();
+      let total_inverse_mass = inverse_mass + entity_inverse_mass;
+      if total_inverse_mass == 0.0 {
+        return Some(time);
+      }
+      
+      let normal = contact.normal;
+      let relative_velocity = entity.velocity() - self.velocity();
+      let closing_speed = relative_velocity.dot(normal);
+      
+      // only while closing, so the second collide_with of the pair doesn't bounce them back
+      if closing_speed < 0.0 {
+        let restitution = self.data().restitution.min(entity.data().restitution);
+        let impulse = -(1.0 + restitution)*closing_speed / total_inverse_mass;
+        
+        let tangent = relative_velocity - normal*closing_speed;
+        let friction_impulse = if tangent.magnitude2() > 0.0 {
+          let friction = (self.data().friction*entity.data().friction).sqrt();
+          let tangent = tangent.normalize();
+          (-relative_velocity.dot(tangent) / total_inverse_mass).max(-friction*impulse).min(friction*impulse)*tangent
+        } else {
+          Vector2::new(0.0, 0.0)
+        };
+        
+        let push = normal*impulse + friction_impulse;
+        let velocity = self.velocity() - push*inverse_mass;
+        let entity_velocity = entity.velocity() + push*entity_inverse_mass;
+        self.set_velocity(velocity);
+        entity.set_velocity(entity_velocity);
+        
+        if harmful {
+          let damage = impulse*COLLISION_DAMAGE;
+          self.hit(Damage::new(damage*entity.data().impact_damage, DamageType::Collision));
+          entity.hit(Damage::new(damage*self.data().impact_damage, DamageType::Collision));
         }
       }
-      if collided { break; }
+      
+      let correction = normal*((contact.depth - CORRECTION_SLOP).max(0.0)*CORRECTION_PERCENT / total_inverse_mass);
+      if correction.magnitude2() > 0.0 {
+        let position = self.position() - correction*inverse_mass;
+        let entity_position = entity.position() + correction*entity_inverse_mass;
+        self.set_position(position);
+        entity.set_position(entity_position);
+      }
     }
+    
+    time
   }
   
   fn return_projectiles(&mut self) -> Vec<BoxProjectile> {
@@ -532,7 +1372,7 @@ pub trait Entity: EntityClone {
     projectiles
   }
   
-  fn return_buffs(&mut self) -> Vec<Box<Buff>> {
+  fn return_buffs(&mut self) -> Vec<BoxBuff> {
     let buffs = self.data().buffs.clone();
     self.mut_data().buffs.clear();
     
@@ -551,9 +1391,27 @@ pub trait Entity: EntityClone {
     let colour = Vector4::new(0.0, 1.0, 0.0, 0.5);
     draw_calls.push(DrawCall::draw_coloured(position, size, colour, 0.0));
     
-    let size = Vector2::new(40.0*(self.data().shield / self.data().max_health), 15.0);
-    let colour = Vector4::new(0.0, 0.0, 1.0, 0.5);
+    let arcs = self.shield_arcs();
+    let shield = arcs.iter().map(|&arc| self.shield_arc(arc).0).sum::<f32>() / arcs.len() as f32;
+    let size = Vector2::new(40.0*(shield / self.data().max_health), 15.0);
+    let colour = if self.data().shield_broken || self.data().rear_shield_broken {
+      Vector4::new(0.5, 0.5, 0.5, 0.5)
+    } else {
+      Vector4::new(0.0, 0.0, 1.0, 0.5)
+    };
     draw_calls.push(DrawCall::draw_coloured(position, size, colour, 0.0));
+    
+    let energy_position = position + Vector2::new(0.0, 15.0);
+    let size = Vector2::new(40.0*(self.data().energy / self.data().max_energy), 5.0);
+    let colour = Vector4::new(1.0, 1.0, 0.0, 0.5);
+    draw_calls.push(DrawCall::draw_coloured(energy_position, size, colour, 0.0));
+    
+    // a square for each status effect under the health bar, taller for each stack
+    for (i, (kind, stacks)) in self.data().statuses.iter().enumerate() {
+      let height = 6.0 + 2.0*(*stacks as f32 - 1.0);
+      let status_position = position + Vector2::new(-14.0 + 8.0*i as f32, -8.0 - height*0.5);
+      draw_calls.push(DrawCall::draw_coloured(status_position, Vector2::new(6.0, height), kind.colour(), 0.0));
+    }
   }
   
   fn draw(&self, draw_calls: &mut Vec<DrawCall>) {
@@ -561,16 +1419,19 @@ pub trait Entity: EntityClone {
                                                          self.data().rotation, 
                                                          self.data().texture.to_string(), 
                                                          Vector3::new(0,0, 1)));
-    if self.data().shield > 0.0 {
+    let shielded = self.shield_arcs().iter().any(|&arc| {
+      let (shield, broken) = self.shield_arc(arc);
+      shield > 0.0 && !broken
+    });
+    if shielded {
       draw_calls.push(DrawCall::add_instanced_sprite_sheet_with_alpha(self.data().position, self.data().size, 
-                                                           0.0,//self.data().rotation, 
+                                                           self.data().rotation, 
                                                            "BlueShield".to_string(), 
                                                            Vector3::new(0, 0, 1), 0.5));
     }
   }
   
   fn draw_collision_circles(&self, draw_calls: &mut Vec<DrawCall>) {
-    let circles = self.collision_circles();
     let colour = if self.data().hostility.is_hostile() {
       Vector4::new(1.0, 0.0, 0.0, 1.0)
     } else if self.data().hostility.is_friendly() {
@@ -579,8 +1440,18 @@ pub trait Entity: EntityClone {
       Vector4::new(0.0, 1.0, 0.0, 1.0)
     };
     
-    for circle in &circles {
-      draw_calls.push(DrawCall::draw_coloured(circle.xy(), Vector2::new(circle.z*2.0, circle.z*2.0), colour, 0.0));
+    for collider in &self.colliders() {
+      collider.draw(colour, draw_calls);
+    }
+    
+    let colliders = self.section_colliders();
+    for (section, collider) in self.data().ship_sections.iter().zip(colliders.iter()) {
+      let colour = if section.is_destroyed() {
+        Vector4::new(0.3, 0.3, 0.3, 1.0)
+      } else {
+        Vector4::new(colour.x*0.5, colour.y*0.5, colour.z*0.5, 1.0)
+      };
+      collider.draw(colour, draw_calls);
     }
   }
 }
@@ -26,6 +26,10 @@ impl ShipSection for Hull {
     &mut self.data
   }
   
+  fn is_vital(&self) -> bool {
+    true
+  }
+  
   fn draw(&self, _draw_calls: &mut Vec<DrawCall>) {
     
   }
@@ -1,20 +1,30 @@
 use maat_graphics::DrawCall;
 
 use crate::modules::entities::sections::{ShipSection, ShipSectionData};
+use crate::modules::damage::{DamageType, Resistances};
 
 use crate::cgmath::Vector2;
 
 #[derive(Clone)]
 pub struct HullMaterial {
   data: ShipSectionData,
+  resistances: Resistances,
 }
 
 impl HullMaterial {
   pub fn new(offset: Vector2<f32>, size: Vector2<f32>) -> HullMaterial {
     HullMaterial {
       data: ShipSectionData::new(offset, size),
+      resistances: Resistances::new().with_resistance(DamageType::Kinetic, 0.2)
+                                     .with_resistance(DamageType::Thermal, 0.1)
+                                     .with_resistance(DamageType::Collision, 0.3),
     }
   }
+  
+  pub fn with_resistances(mut self, resistances: Resistances) -> HullMaterial {
+    self.resistances = resistances;
+    self
+  }
 }
 
 impl ShipSection for HullMaterial {
@@ -26,6 +36,10 @@ impl ShipSection for HullMaterial {
     &mut self.data
   }
   
+  fn resistances(&self) -> Option<Resistances> {
+    Some(self.resistances)
+  }
+  
   fn draw(&self, _draw_calls: &mut Vec<DrawCall>) {
   }
 }
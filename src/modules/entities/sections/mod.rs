@@ -5,6 +5,7 @@ pub use self::weapon_mount::WeaponMount;
 pub use self::wing::Wing;
 pub use self::repair_bay::RepairBay;
 pub use self::hull_material::HullMaterial;
+pub use self::reactor::Reactor;
 
 mod modular_slots;
 mod hull;
@@ -13,27 +14,49 @@ mod weapon_mount;
 mod wing;
 mod repair_bay;
 mod hull_material;
+mod reactor;
 use maat_graphics::DrawCall;
 
+use maat_graphics::math;
+
 use crate::modules::abilities::BoxAbility;
+use crate::modules::colliders::Collider;
+use crate::modules::damage::Resistances;
 
 use crate::cgmath::Vector2;
 
+// Health per square unit of section
+const SECTION_HEALTH_PER_AREA: f32 = 0.02;
+// Energy per second per unit of thrust
+const POWER_PER_THRUST: f32 = 0.001;
+
 #[derive(Clone)]
 pub struct ShipSectionData {
   modular_slots: Vec<ModularSlot>,
   offset: Vector2<f32>,
   size: Vector2<f32>,
+  health: f32,
+  max_health: f32,
 }
 
 impl ShipSectionData {
   pub fn new(offset: Vector2<f32>, size: Vector2<f32>) -> ShipSectionData {
+    let health = size.x*size.y*SECTION_HEALTH_PER_AREA;
+    
     ShipSectionData {
       modular_slots: Vec::new(),
       offset,
       size,
+      health,
+      max_health: health,
     }
   }
+  
+  pub fn with_health(mut self, health: f32) -> ShipSectionData {
+    self.health = health;
+    self.max_health = health;
+    self
+  }
 }
 
 pub trait ShipSectionClone {
@@ -64,7 +87,85 @@ pub trait ShipSection: ShipSectionClone {
     self.data().size
   }
   
+  fn health(&self) -> f32 {
+    self.data().health
+  }
+  
+  fn max_health(&self) -> f32 {
+    self.data().max_health
+  }
+  
+  fn is_destroyed(&self) -> bool {
+    self.data().health <= 0.0
+  }
+  
+  // Whether losing it loses the ship
+  fn is_vital(&self) -> bool {
+    false
+  }
+  
+  // Force the section pushes with, 0 for anything but thrusters
+  fn thrust(&self) -> f32 {
+    0.0
+  }
+  
+  // Energy per second it draws while pushing at full thrust
+  fn power_draw(&self) -> f32 {
+    self.thrust()*POWER_PER_THRUST
+  }
+  
+  // Energy per second it adds to the ship, 0 for anything but reactors
+  fn generation(&self) -> f32 {
+    0.0
+  }
+  
+  // What the ship's armour shrugs off, None for anything but hull material
+  fn resistances(&self) -> Option<Resistances> {
+    None
+  }
+  
+  // Most torque the section can turn the ship with, from a force at its offset from the centre
+  // of a layout scaled by scale, see Entity::section_layout
+  fn torque(&self, _centre: Vector2<f32>, _scale: f32) -> f32 {
+    0.0
+  }
+  
+  // Where the section sits in the world, for a ship at position facing rotation whose layout
+  // is scaled by scale about centre
+  fn collider(&self, position: Vector2<f32>, rotation: f32, centre: Vector2<f32>, scale: f32) -> Collider {
+    let offset = (self.data().offset-centre)*scale;
+    let angle = math::to_radians(rotation);
+    let x_axis = Vector2::new(angle.cos(), angle.sin());
+    let y_axis = Vector2::new(-angle.sin(), angle.cos());
+    
+    Collider::oriented_box(position + x_axis*offset.x + y_axis*offset.y, self.data().size*scale, rotation)
+  }
+  
+  fn set_max_health(&mut self, max_health: f32) {
+    self.mut_data().max_health = max_health;
+    self.mut_data().health = max_health;
+  }
+  
+  // Returns true if this hit destroyed it
+  fn damage(&mut self, damage: f32) -> bool {
+    if self.is_destroyed() {
+      return false;
+    }
+    
+    self.mut_data().health = (self.data().health - damage).max(0.0);
+    self.is_destroyed()
+  }
+  
+  fn repair(&mut self, health: f32) {
+    self.mut_data().health = (self.data().health + health).min(self.data().max_health);
+  }
+  
+  // Nothing in the slots of a destroyed section can be used
   fn active_abilities(&self) -> Vec<BoxAbility> {
+    if self.is_destroyed() {
+      return Vec::new();
+    }
+    
     let mut abilities = Vec::new();
     for slot in &self.data().modular_slots {
       if let Some(ability) = slot.ability() {
@@ -79,5 +180,29 @@ pub trait ShipSection: ShipSectionClone {
     self.data().modular_slots.clone()
   }
   
+  // Health per second this section gives back to its ship while it sits still to repair
+  fn repair_rate(&self) -> f32 {
+    0.0
+  }
+  
+  fn add_modular_slot(&mut self, slot: ModularSlot) {
+    self.mut_data().modular_slots.push(slot);
+  }
+  
+  // Puts ability in the slot with slot_id, replacing what was there. False if it has no such slot.
+  fn install_ability(&mut self, slot_id: i32, ability: BoxAbility) -> bool {
+    match self.mut_data().modular_slots.iter_mut().find(|slot| slot.id() == slot_id) {
+      Some(slot) => {
+        slot.gain_ability(ability);
+        true
+      },
+      None => false,
+    }
+  }
+  
+  fn remove_ability(&mut self, slot_id: i32) -> Option<BoxAbility> {
+    self.mut_data().modular_slots.iter_mut().find(|slot| slot.id() == slot_id).and_then(|slot| slot.remove_ability())
+  }
+  
   fn draw(&self, draw_calls: &mut Vec<DrawCall>);
 }
@@ -16,6 +16,24 @@ impl ModularSlot {
     }
   }
   
+  pub fn with_ability(mut self, ability: BoxAbility) -> ModularSlot {
+    self.ability = Some(ability);
+    self
+  }
+  
+  pub fn id(&self) -> i32 {
+    self.id
+  }
+  
+  // The slot this one fires together with
+  pub fn linked_id(&self) -> Option<i32> {
+    self.linked_id
+  }
+  
+  pub fn has_ability(&self) -> bool {
+    self.ability.is_some()
+  }
+  
   pub fn ability(&self) -> Option<BoxAbility> {
     self.ability.clone()
   }
@@ -28,7 +46,7 @@ impl ModularSlot {
     self.ability = Some(ability);
   }
   
-  pub fn remove_ability(&mut self) {
-    self.ability = None;
+  pub fn remove_ability(&mut self) -> Option<BoxAbility> {
+    self.ability.take()
   }
 }
@@ -0,0 +1,46 @@
+use maat_graphics::DrawCall;
+
+use crate::modules::entities::sections::{ShipSection, ShipSectionData};
+
+use crate::cgmath::Vector2;
+
+// Energy per second per square unit of reactor
+const GENERATION_PER_AREA: f32 = 0.01;
+
+#[derive(Clone)]
+pub struct Reactor {
+  data: ShipSectionData,
+  generation: f32,
+}
+
+impl Reactor {
+  pub fn new(offset: Vector2<f32>, size: Vector2<f32>) -> Reactor {
+    Reactor {
+      data: ShipSectionData::new(offset, size),
+      generation: size.x*size.y*GENERATION_PER_AREA,
+    }
+  }
+  
+  pub fn with_generation(mut self, generation: f32) -> Reactor {
+    self.generation = generation;
+    self
+  }
+}
+
+impl ShipSection for Reactor {
+  fn data(&self) -> &ShipSectionData {
+    &self.data
+  }
+  
+  fn mut_data(&mut self) -> &mut ShipSectionData {
+    &mut self.data
+  }
+  
+  fn generation(&self) -> f32 {
+    self.generation
+  }
+  
+  fn draw(&self, _draw_calls: &mut Vec<DrawCall>) {
+    
+  }
+}
@@ -4,6 +4,8 @@ use crate::modules::entities::sections::{ShipSection, ShipSectionData};
 
 use crate::cgmath::Vector2;
 
+const REPAIR_RATE: f32 = 10.0;
+
 #[derive(Clone)]
 pub struct RepairBay {
   data: ShipSectionData,
@@ -26,6 +28,10 @@ impl ShipSection for RepairBay {
     &mut self.data
   }
   
+  fn repair_rate(&self) -> f32 {
+    REPAIR_RATE
+  }
+  
   fn draw(&self, _draw_calls: &mut Vec<DrawCall>) {
     
   }
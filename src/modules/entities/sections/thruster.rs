@@ -2,19 +2,29 @@ use maat_graphics::DrawCall;
 
 use crate::modules::entities::sections::{ShipSection, ShipSectionData};
 
-use crate::cgmath::Vector2;
+use crate::cgmath::{Vector2, InnerSpace};
+
+// Force per square unit of thruster
+const THRUST_PER_AREA: f32 = 2.0;
 
 #[derive(Clone)]
 pub struct Thruster {
   data: ShipSectionData,
+  thrust: f32,
 }
 
 impl Thruster {
   pub fn new(offset: Vector2<f32>, size: Vector2<f32>) -> Thruster {
     Thruster {
       data: ShipSectionData::new(offset, size),
+      thrust: size.x*size.y*THRUST_PER_AREA,
     }
   }
+  
+  pub fn with_thrust(mut self, thrust: f32) -> Thruster {
+    self.thrust = thrust;
+    self
+  }
 }
 
 impl ShipSection for Thruster {
@@ -26,6 +36,15 @@ impl ShipSection for Thruster {
     &mut self.data
   }
   
+  fn thrust(&self) -> f32 {
+    self.thrust
+  }
+  
+  // Gimballed, so it can push square to its offset from the centre
+  fn torque(&self, centre: Vector2<f32>, scale: f32) -> f32 {
+    ((self.offset()-centre)*scale).magnitude()*self.thrust
+  }
+  
   fn draw(&self, _draw_calls: &mut Vec<DrawCall>) {
     
   }
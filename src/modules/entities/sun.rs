@@ -1,7 +1,14 @@
 use crate::modules::entities::{Entity, BoxEntity, EntityData};
+use crate::modules::gravity::GravityWell;
+use crate::modules::damage::{DamageType, Resistances};
+use crate::modules::buffs::StatusKind;
 
 use crate::cgmath::Vector2;
 
+// Pull at one unit away, enough to hold a ship at top speed in orbit a few hundred units out
+const SUN_GRAVITY: f32 = 200000000.0;
+const SUN_GRAVITY_RANGE: f32 = 6000.0;
+
 #[derive(Clone)]
 pub struct Sun {
   data: EntityData,
@@ -17,8 +24,19 @@ impl Sun {
       data: EntityData::new(position, size, texture.to_string())
                         .with_max_velocity(0.0)
                         .with_inertia(0.0)
+                        .immovable()
+                        .with_impact_damage(10.0)
+                        .with_gravity_well(GravityWell::new(SUN_GRAVITY)
+                                             .with_min_radius(size.x*0.5*0.7)
+                                             .with_range(SUN_GRAVITY_RANGE))
                         .with_health(1000.0)
-                        .with_health_regen(500.0),
+                        .with_health_regen(500.0)
+                        .with_resistances(Resistances::new().with_resistance(DamageType::Thermal, 1.0)
+                                                            .with_resistance(DamageType::Kinetic, 0.5)
+                                                            .with_resistance(DamageType::Energy, -0.25))
+                        .with_immunity(StatusKind::Burn)
+                        .with_immunity(StatusKind::Stun)
+                        .with_immunity(StatusKind::Disarm),
     }
   }
   
@@ -48,7 +66,7 @@ impl Entity for Sun {
     vec!((Vector2::new(0.0, 0.0), radius))
   }
   
-  fn collide_with(&mut self, entity: &mut BoxEntity) {
-    self.entity_collision(entity, 10.0, 900.0);
+  fn collide_with(&mut self, entity: &mut BoxEntity) -> Option<f32> {
+    self.entity_collision(entity, true)
   }
 }
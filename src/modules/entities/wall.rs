@@ -1,6 +1,6 @@
-use maat_graphics::math;
-
 use crate::modules::entities::{Entity, BoxEntity, EntityData};
+use crate::modules::colliders::Collider;
+use crate::modules::damage::{DamageType, Resistances};
 
 use crate::cgmath::Vector2;
 
@@ -19,8 +19,14 @@ impl Wall {
       data: EntityData::new(position, size, texture.to_string())
                         .with_max_velocity(0.0)
                         .with_inertia(0.0)
+                        .immovable()
+                        .with_impact_damage(0.02)
                         .with_health(1000.0)
-                        .with_health_regen(500.0),
+                        .with_health_regen(500.0)
+                        .with_resistances(Resistances::new().with_resistance(DamageType::Kinetic, 0.75)
+                                                            .with_resistance(DamageType::Thermal, 0.5)
+                                                            .with_resistance(DamageType::Energy, 0.5)
+                                                            .with_resistance(DamageType::Collision, 1.0)),
     }
   }
   
@@ -44,32 +50,16 @@ impl Entity for Wall {
     &mut self.data
   }
   
+  // Collides as the box it's drawn as, see colliders
   fn collision_information(&self) -> Vec<(Vector2<f32>, f32)> {
-    let radius = self.data().size.x.min(self.data().size.y)*0.5;
-    
-    let goal = self.data().size.x.max(self.data().size.y)*0.5;
-    
-    let mut wall_circles = Vec::new();
-    wall_circles.push((Vector2::new(0.0, 0.0), radius));
-    
-    let _pos = self.data().position;
-    let rotation = self.data().rotation;
-    
-    let num_circles = ((goal / radius).floor()*0.5) as i32 -1;
-    
-    for i in 0..num_circles {
-      
-      let offset = Vector2::new(radius*2.5*(i+1) as f32*(math::to_radians(rotation)).cos(),
-                                radius*2.5*(i+1) as f32*(math::to_radians(rotation)).sin());
-      
-      wall_circles.push((offset, radius));
-      wall_circles.push((-offset, radius));
-    }
-    
-    wall_circles
+    Vec::new()
+  }
+  
+  fn colliders(&self) -> Vec<Collider> {
+    vec!(Collider::oriented_box(self.data().position, self.data().size, self.data().rotation))
   }
   
-  fn collide_with(&mut self, entity: &mut BoxEntity) {
-    self.entity_collision(entity, 0.01, 200.0);
+  fn collide_with(&mut self, entity: &mut BoxEntity) -> Option<f32> {
+    self.entity_collision(entity, true)
   }
 }
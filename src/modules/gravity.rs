@@ -0,0 +1,84 @@
+use crate::cgmath::{Vector2, InnerSpace};
+
+// Pulls other entities and projectiles towards whatever carries it, falling off with the
+// square of the distance. Strength is the pull, in units per second squared, at one unit away.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GravityWell {
+  strength: f32,
+  // closer than this the pull stops growing, so nothing is flung out of the middle
+  min_radius: f32,
+  // 0 for no limit
+  range: f32,
+}
+
+impl GravityWell {
+  pub fn new(strength: f32) -> GravityWell {
+    GravityWell {
+      strength,
+      min_radius: 1.0,
+      range: 0.0,
+    }
+  }
+  
+  pub fn with_min_radius(mut self, min_radius: f32) -> GravityWell {
+    self.min_radius = min_radius;
+    self
+  }
+  
+  pub fn with_range(mut self, range: f32) -> GravityWell {
+    self.range = range;
+    self
+  }
+  
+  pub fn strength(&self) -> f32 {
+    self.strength
+  }
+  
+  // Acceleration on something at position from a well at center
+  pub fn acceleration(&self, center: Vector2<f32>, position: Vector2<f32>) -> Vector2<f32> {
+    let offset = center - position;
+    let distance = offset.magnitude();
+    if distance == 0.0 || (self.range > 0.0 && distance > self.range) {
+      return Vector2::new(0.0, 0.0);
+    }
+    
+    let radius = distance.max(self.min_radius);
+    offset/distance * self.strength/(radius*radius)
+  }
+}
+
+// Every well pulling this tick, with the id of the entity carrying it so it doesn't pull itself
+#[derive(Clone)]
+pub struct GravityField {
+  wells: Vec<(u32, Vector2<f32>, GravityWell)>,
+}
+
+impl GravityField {
+  pub fn new() -> GravityField {
+    GravityField {
+      wells: Vec::new(),
+    }
+  }
+  
+  pub fn add(&mut self, id: u32, position: Vector2<f32>, well: GravityWell) {
+    self.wells.push((id, position, well));
+  }
+  
+  pub fn is_empty(&self) -> bool {
+    self.wells.is_empty()
+  }
+  
+  // Pull at position from every well not carried by id, 0 for projectiles
+  pub fn acceleration_at(&self, position: Vector2<f32>, id: u32) -> Vector2<f32> {
+    let mut acceleration = Vector2::new(0.0, 0.0);
+    for (well_id, center, well) in &self.wells {
+      if id != 0 && *well_id == id {
+        continue;
+      }
+      
+      acceleration += well.acceleration(*center, position);
+    }
+    
+    acceleration
+  }
+}
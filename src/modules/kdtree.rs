@@ -4,6 +4,139 @@ use std::cmp;
 
 use crate::cgmath::{Vector2, Vector4};
 
+fn overlaps(min_a: Vector2<f32>, max_a: Vector2<f32>, min_b: Vector2<f32>, max_b: Vector2<f32>) -> bool {
+  min_a.x <= max_b.x && max_a.x >= min_b.x && min_a.y <= max_b.y && max_a.y >= min_b.y
+}
+
+#[derive(Clone)]
+struct KdNode {
+  object: usize,
+  left: Option<usize>,
+  right: Option<usize>,
+  // box around every object in this subtree, so whole branches can be skipped
+  min: Vector2<f32>,
+  max: Vector2<f32>,
+}
+
+// A k-d tree over object bounds, split on the centre of each box alternating x and y.
+// Objects and nodes are kept between builds so rebuilding every tick reuses their memory.
+#[derive(Clone)]
+pub struct KdTree<T> {
+  objects: Vec<(T, Vector2<f32>, Vector2<f32>)>,
+  nodes: Vec<KdNode>,
+  root: Option<usize>,
+  scratch: Vec<usize>,
+}
+
+impl<T: Clone> KdTree<T> {
+  pub fn new() -> KdTree<T> {
+    KdTree {
+      objects: Vec::new(),
+      nodes: Vec::new(),
+      root: None,
+      scratch: Vec::new(),
+    }
+  }
+  
+  pub fn clear(&mut self) {
+    self.objects.clear();
+    self.nodes.clear();
+    self.root = None;
+  }
+  
+  pub fn len(&self) -> usize {
+    self.objects.len()
+  }
+  
+  pub fn object(&self, index: usize) -> &T {
+    &self.objects[index].0
+  }
+  
+  pub fn bounds(&self, index: usize) -> (Vector2<f32>, Vector2<f32>) {
+    (self.objects[index].1, self.objects[index].2)
+  }
+  
+  // Only adds the object, call build once everything is in
+  pub fn insert(&mut self, object: T, min: Vector2<f32>, max: Vector2<f32>) {
+    self.objects.push((object, min, max));
+  }
+  
+  pub fn build(&mut self) {
+    self.nodes.clear();
+    
+    let mut indices = ::std::mem::replace(&mut self.scratch, Vec::new());
+    indices.clear();
+    indices.extend(0..self.objects.len());
+    
+    let len = indices.len();
+    self.root = self.build_node(&mut indices, 0, len, 0);
+    
+    self.scratch = indices;
+  }
+  
+  fn build_node(&mut self, indices: &mut Vec<usize>, start: usize, end: usize, depth: u32) -> Option<usize> {
+    if start >= end {
+      return None;
+    }
+    
+    {
+      let objects = &self.objects;
+      let centre = |index: &usize| {
+        let (_, min, max) = &objects[*index];
+        if depth % 2 == 0 { min.x + max.x } else { min.y + max.y }
+      };
+      indices[start..end].sort_by(|a, b| centre(a).partial_cmp(&centre(b)).unwrap_or(cmp::Ordering::Equal).then(a.cmp(b)));
+    }
+    
+    let median = start + (end - start)/2;
+    let object = indices[median];
+    let left = self.build_node(indices, start, median, depth+1);
+    let right = self.build_node(indices, median+1, end, depth+1);
+    
+    let (mut min, mut max) = (self.objects[object].1, self.objects[object].2);
+    for child in left.iter().chain(right.iter()) {
+      let node = &self.nodes[*child];
+      min = Vector2::new(min.x.min(node.min.x), min.y.min(node.min.y));
+      max = Vector2::new(max.x.max(node.max.x), max.y.max(node.max.y));
+    }
+    
+    self.nodes.push(KdNode {
+      object,
+      left,
+      right,
+      min,
+      max,
+    });
+    
+    Some(self.nodes.len()-1)
+  }
+  
+  // Indices of every object whose bounds overlap the box, in insertion order
+  pub fn query(&self, min: Vector2<f32>, max: Vector2<f32>) -> Vec<usize> {
+    let mut found = Vec::new();
+    
+    let mut stack = Vec::new();
+    stack.extend(self.root);
+    while let Some(node) = stack.pop() {
+      let node = &self.nodes[node];
+      if !overlaps(node.min, node.max, min, max) {
+        continue;
+      }
+      
+      let (_, object_min, object_max) = &self.objects[node.object];
+      if overlaps(*object_min, *object_max, min, max) {
+        found.push(node.object);
+      }
+      
+      stack.extend(node.left);
+      stack.extend(node.right);
+    }
+    found.sort();
+    
+    found
+  }
+}
+
 #[derive(Debug, Clone)]
 pub struct Node {
   location: Vector2<f32>,
@@ -131,7 +264,7 @@ impl Node {
     let axis = depth % k;
     
     let mut new_parent_bound = 0.0;
-
+    
     match axis {
       0 => {
         let mut upper_height_constraint = height;
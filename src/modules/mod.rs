@@ -10,6 +10,14 @@ pub mod buffs;
 pub mod ui;
 pub mod areas;
 pub mod spatial_hash;
+pub mod colliders;
+pub mod gravity;
+pub mod damage;
+pub mod stats;
+pub mod broadphase;
 pub mod kdtree;
+pub mod world;
+pub mod replay;
+pub mod scripting;
 mod animation;
 mod collisions;
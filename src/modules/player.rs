@@ -1,6 +1,5 @@
-use crate::modules::entities::{BoxEntity};
-use crate::modules::abilities::{Ability, NoAbility, BoxAbility, SingleShot, DoubleShot, Move, 
-                                Shatter, Shield, Dash, Laser, Haste};
+use crate::modules::entities::{BoxEntity, Hostility};
+use crate::modules::abilities::{Ability, NoAbility, BoxAbility, Loadout, ability_by_name};
 
 use crate::cgmath::{Vector2, InnerSpace};
 
@@ -16,134 +15,228 @@ pub enum _AbilityPosition {
   _Ability4, // r
 }
 
-pub struct Input {
-  abilities: Vec<BoxAbility>,
-  left_click_ability: usize,
-  middle_click_ability: usize,
-  right_click_ability: usize,
-  ability_one: usize,
-  ability_two: usize,
-  ability_three: usize,
-  ability_four: usize,
+#[derive(Clone, Copy, PartialEq)]
+pub struct InputState {
+  pub left_stick_position: Vector2<f32>,
+  pub a_button_pressed: bool,
+  pub right_trigger_pressed: bool,
+  pub mouse_pos: Vector2<f32>,
+  pub left_mouse: bool,
+  pub middle_mouse: bool,
+  pub right_mouse: bool,
+  pub q_pressed: bool,
+  pub w_pressed: bool,
+  pub e_pressed: bool,
+  pub r_pressed: bool,
 }
 
-impl Input {
-  pub fn new() -> Input {
-    let mut double_shot = Box::new(DoubleShot::new());
-    double_shot.add_passive(Box::new(Shatter::new()));
+impl InputState {
+  pub fn new() -> InputState {
+    InputState {
+      left_stick_position: Vector2::new(0.0, 0.0),
+      a_button_pressed: false,
+      right_trigger_pressed: false,
+      mouse_pos: Vector2::new(0.0, 0.0),
+      left_mouse: false,
+      middle_mouse: false,
+      right_mouse: false,
+      q_pressed: false,
+      w_pressed: false,
+      e_pressed: false,
+      r_pressed: false,
+    }
+  }
+}
+
+// Asks for an ability, by name, to go into one of the ship's sections. Applied by the World at
+// the start of a tick and recorded with that tick's input, so replays refit at the same moment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Refit {
+  pub section: usize,
+  pub ability: String,
+}
+
+impl Refit {
+  pub fn new(section: usize, ability: &str) -> Refit {
+    Refit {
+      section,
+      ability: ability.to_string(),
+    }
+  }
+  
+  // Puts the ability in the section's first empty slot, or its first slot if they are all full.
+  // NoAbility empties every slot on the section.
+  pub fn apply(&self, ship: &mut BoxEntity) {
+    let slots = match ship.ship_sections().get(self.section) {
+      Some(ship_section) => ship_section.modular_slots(),
+      None => return,
+    };
     
-    let mut abilities: Vec<BoxAbility> = vec!(Box::new(NoAbility::new()),
-      Box::new(Move::new()),
-      Box::new(Shield::new()),
-      double_shot,
-      Box::new(Dash::new()),
-      Box::new(Laser::new()),
-      Box::new(Haste::new()),
-      Box::new(SingleShot::new()),
-    );
+    if self.ability == "NoAbility" {
+      for slot in &slots {
+        ship.remove_ability(slot.id());
+      }
+      return;
+    }
     
-    let mut input = Input {
-      abilities,
-      left_click_ability: 1,
-      middle_click_ability: 2,
-      right_click_ability: 3,
-      ability_one: 4,
-      ability_two: 5,
-      ability_three: 6,
-      ability_four: 7,
+    let ability = match ability_by_name(&self.ability) {
+      Some(ability) => ability,
+      None => {
+        println!("Refit: unknown ability {}", self.ability);
+        return;
+      }
     };
     
-    
-    input
+    if let Some(slot) = slots.iter().find(|slot| !slot.has_ability()).or(slots.first()) {
+      ship.install_ability(slot.id(), ability);
+    }
+  }
+}
+
+pub struct Input {
+  no_ability: BoxAbility,
+  // whatever is in the ship's slots
+  loadout: Loadout,
+  // slot ids, so bindings stay with their slot however the loadout changes. None for no ability.
+  left_click_ability: Option<i32>,
+  middle_click_ability: Option<i32>,
+  right_click_ability: Option<i32>,
+  ability_one: Option<i32>,
+  ability_two: Option<i32>,
+  ability_three: Option<i32>,
+  ability_four: Option<i32>,
+}
+
+impl Input {
+  pub fn new() -> Input {
+    Input {
+      no_ability: Box::new(NoAbility::new()),
+      loadout: Loadout::new(),
+      left_click_ability: Some(1),
+      middle_click_ability: Some(2),
+      right_click_ability: Some(3),
+      ability_one: Some(4),
+      ability_two: Some(5),
+      ability_three: Some(6),
+      ability_four: Some(7),
+    }
   }
   
-  pub fn get_ability_cooldowns(&self) -> Vec<f32> {
-    let mut cd: [f32; 7] = [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
-    
-    cd[0] = self.abilities[self.ability_one].percentage_cooldown_left();
-    cd[1] = self.abilities[self.ability_two].percentage_cooldown_left();
-    cd[2] = self.abilities[self.ability_three].percentage_cooldown_left();
-    cd[3] = self.abilities[self.ability_four].percentage_cooldown_left();
-    cd[4] = self.abilities[self.left_click_ability].percentage_cooldown_left();
-    cd[5] = self.abilities[self.middle_click_ability].percentage_cooldown_left();
-    cd[6] = self.abilities[self.right_click_ability].percentage_cooldown_left();
-    
-    cd.to_vec()
+  // Picks up abilities installed in or removed from the ship's slots
+  pub fn sync(&mut self, ship: &BoxEntity) {
+    self.loadout.sync(ship);
+  }
+  
+  // The ability in the bound slot, NoAbility if the slot is empty, destroyed or doesn't exist
+  fn bound(&self, binding: Option<i32>) -> &BoxAbility {
+    binding.and_then(|slot_id| self.loadout.slot_index(slot_id))
+           .and_then(|index| self.loadout.ability(index))
+           .unwrap_or(&self.no_ability)
   }
   
+  pub fn get_ability_cooldowns(&self) -> Vec<f32> {
+    self.bindings().iter().map(|&binding| self.bound(binding).percentage_cooldown_left()).collect()
+  }
   
   pub fn get_ability_textures(&self) -> Vec<String> {
-    let mut textures: [String; 7] = ["NoAbility".to_string(), "NoAbility".to_string(), 
-                                     "NoAbility".to_string(), "NoAbility".to_string(), 
-                                     "NoAbility".to_string(), "NoAbility".to_string(),
-                                     "NoAbility".to_string()];
-    
-    textures[0] = self.abilities[self.ability_one].texture();
-    textures[1] = self.abilities[self.ability_two].texture();
-    textures[2] = self.abilities[self.ability_three].texture();
-    textures[3] = self.abilities[self.ability_four].texture();
-    textures[4] = self.abilities[self.left_click_ability].texture();
-    textures[5] = self.abilities[self.middle_click_ability].texture();
-    textures[6] = self.abilities[self.right_click_ability].texture();
-    
-    textures.to_vec()
+    self.bindings().iter().map(|&binding| self.bound(binding).texture()).collect()
   }
   
   pub fn all_abilities(&self) -> Vec<BoxAbility> {
-    self.abilities.clone()
+    let mut abilities = vec!(self.no_ability.clone());
+    abilities.extend(self.loadout.abilities().iter().cloned());
+    abilities
   }
   
   pub fn ability(&self, index: usize) -> &BoxAbility {
-    if index+1 < self.abilities.len() {
-      &self.abilities[index+1]
-    } else {
-      &self.abilities[0]
-    }
+    self.loadout.ability(index).unwrap_or(&self.no_ability)
   }
   
   pub fn mut_ability(&mut self, index: usize) -> &mut BoxAbility {
-    if index+1 < self.abilities.len() {
-      &mut self.abilities[index+1]
-    } else {
-      &mut self.abilities[0]
+    match self.loadout.mut_ability(index) {
+      Some(ability) => ability,
+      None => &mut self.no_ability,
+    }
+  }
+  
+  // Slot ids bound to q, w, e, r, left, middle and right mouse
+  pub fn bindings(&self) -> [Option<i32>; 7] {
+    [self.ability_one, self.ability_two, self.ability_three, self.ability_four,
+     self.left_click_ability, self.middle_click_ability, self.right_click_ability]
+  }
+  
+  // Bindings to slots ship doesn't have are cleared
+  pub fn set_bindings(&mut self, mut bindings: [Option<i32>; 7], ship: &BoxEntity) {
+    let slots = ship.modular_slots();
+    for binding in &mut bindings {
+      if let Some(slot_id) = *binding {
+        if !slots.iter().any(|slot| slot.id() == slot_id) {
+          *binding = None;
+        }
+      }
     }
+    
+    self.ability_one = bindings[0];
+    self.ability_two = bindings[1];
+    self.ability_three = bindings[2];
+    self.ability_four = bindings[3];
+    self.left_click_ability = bindings[4];
+    self.middle_click_ability = bindings[5];
+    self.right_click_ability = bindings[6];
   }
   
   pub fn update_abilities(&mut self, delta_time: f32) {
-    for ability in &mut self.abilities {
-      ability.update(delta_time);
+    self.loadout.update(delta_time);
+  }
+  
+  // Fires the bound ability and any linked to it
+  fn activate(&mut self, binding: Option<i32>, ship: &mut BoxEntity, target: Vector2<f32>, window_size: Vector2<f32>, hostility: &Hostility) {
+    if let Some(index) = binding.and_then(|slot_id| self.loadout.slot_index(slot_id)) {
+      self.loadout.activate(index, ship, target, window_size, hostility);
+    }
+  }
+  
+  // The slot holding the ability at index in all_abilities, where 0 is no ability
+  fn slot_of(&self, index: usize) -> Option<i32> {
+    if index == 0 {
+      return None;
     }
+    
+    self.loadout.slot_ids().get(index-1).and_then(|slot_id| *slot_id)
   }
   
   pub fn set_ability_one_by_index(&mut self, index: usize) {
-    self.ability_one = index;
+    self.ability_one = self.slot_of(index);
   }
   
   pub fn set_ability_two_by_index(&mut self, index: usize) {
-    self.ability_two = index;
+    self.ability_two = self.slot_of(index);
   }
   
   pub fn set_ability_three_by_index(&mut self, index: usize) {
-    self.ability_three = index;
+    self.ability_three = self.slot_of(index);
   }
   
   pub fn set_ability_four_by_index(&mut self, index: usize) {
-    self.ability_four = index;
+    self.ability_four = self.slot_of(index);
   }
   
   pub fn set_ability_lm_by_index(&mut self, index: usize) {
-    self.left_click_ability = index;
+    self.left_click_ability = self.slot_of(index);
   }
   
   pub fn set_ability_mm_by_index(&mut self, index: usize) {
-    self.middle_click_ability = index;
+    self.middle_click_ability = self.slot_of(index);
   }
   
   pub fn set_ability_rm_by_index(&mut self, index: usize) {
-    self.right_click_ability = index;
+    self.right_click_ability = self.slot_of(index);
   }
   
-  pub fn update(&mut self, ship: &mut BoxEntity, left_stick_position: Vector2<f32>, a_button_pressed: bool, right_trigger_pressed: bool, mouse_pos: Vector2<f32>, left_mouse: bool, middle_mouse: bool, right_mouse: bool, q_pressed: bool, w_pressed: bool, e_pressed: bool, r_pressed: bool, window_size: Vector2<f32>, delta_time: f32) {
+  pub fn update(&mut self, ship: &mut BoxEntity, state: &InputState, window_size: Vector2<f32>, delta_time: f32) {
+    let InputState { left_stick_position: _left_stick_position, a_button_pressed: _a_button_pressed, 
+                     right_trigger_pressed: _right_trigger_pressed, mouse_pos, left_mouse, middle_mouse, 
+                     right_mouse, q_pressed, w_pressed, e_pressed, r_pressed } = *state;
     
     let mut target = mouse_pos;
     let ship_offset = ship.position()-window_size*0.5;
@@ -151,34 +244,15 @@ impl Input {
     
     let hostility = ship.hostility().clone();
     
+    self.sync(ship);
     self.update_abilities(delta_time);
     
-    if q_pressed {
-      self.abilities[self.ability_one].activate(ship, target, window_size, &hostility);
-    }
-    
-    if w_pressed {
-      self.abilities[self.ability_two].activate(ship, target, window_size, &hostility);
-    }
-    
-    if e_pressed {
-      self.abilities[self.ability_three].activate(ship, target, window_size, &hostility);
-    }
-    
-    if r_pressed {
-      self.abilities[self.ability_four].activate(ship, target, window_size, &hostility);
-    }
-    
-    if left_mouse {
-      self.abilities[self.left_click_ability].activate(ship, target, window_size, &hostility);
-    }
-    
-    if middle_mouse {
-      self.abilities[self.middle_click_ability].activate(ship, target, window_size, &hostility);
-    }
-    
-    if right_mouse {
-      self.abilities[self.right_click_ability].activate(ship, target, window_size, &hostility);
+    let pressed = [q_pressed, w_pressed, e_pressed, r_pressed, left_mouse, middle_mouse, right_mouse];
+    let bindings = self.bindings();
+    for i in 0..bindings.len() {
+      if pressed[i] {
+        self.activate(bindings[i], ship, target, window_size, &hostility);
+      }
     }
     
     /*
@@ -221,7 +295,7 @@ impl Input {
       }
     }*/
     
-    ship.set_facing(target);
+    ship.steer_towards(target);
   }
 }
 
@@ -1,5 +1,6 @@
 
 use crate::modules::projectiles::{Projectile, ProjectileData};
+use crate::modules::damage::DamageType;
 
 use crate::cgmath::{Vector2};
 
@@ -34,6 +35,10 @@ impl Projectile for Aoe {
     &mut self.data
   }
   
+  fn damage_type(&self) -> DamageType {
+    DamageType::Thermal
+  }
+  
   fn collision_information(&self) -> Vec<(Vector2<f32>, f32)> {
     let radius = self.data().size.x;
     vec!((Vector2::new(radius*0.5, radius*0.5), radius))
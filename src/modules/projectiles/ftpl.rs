@@ -1,6 +1,7 @@
 use maat_graphics::math;
 
 use crate::modules::projectiles::{Projectile, ProjectileData};
+use crate::modules::damage::DamageType;
 
 use std::f32::consts::PI;
 
@@ -47,6 +48,10 @@ impl Projectile for Ftpl {
     &mut self.data
   }
   
+  fn damage_type(&self) -> DamageType {
+    DamageType::Kinetic
+  }
+  
   fn collision_information(&self) -> Vec<(Vector2<f32>, f32)> {
     let radius = self.data().size.x.min(self.data().size.y)*0.5 * 0.2;
     
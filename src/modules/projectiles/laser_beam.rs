@@ -1,6 +1,8 @@
 use maat_graphics::math;
 
 use crate::modules::projectiles::{Projectile, ProjectileData};
+use crate::modules::colliders::Collider;
+use crate::modules::damage::DamageType;
 
 use std::f32::consts::PI;
 
@@ -45,31 +47,18 @@ impl Projectile for LaserBeam {
     &mut self.data
   }
   
+  fn damage_type(&self) -> DamageType {
+    DamageType::Energy
+  }
+  
+  // Collides as the beam it's drawn as, see colliders
   fn collision_information(&self) -> Vec<(Vector2<f32>, f32)> {
-    let radius = self.data().size.x.min(self.data().size.y)*0.5;
-    
-    let goal = self.data().size.x.max(self.data().size.y)*0.5;
-    
-    let mut laser_circles = Vec::new();
-    laser_circles.push((Vector2::new(0.0, 0.0), radius));
-    
-    let _pos = self.data().position;
-    let rotation = self.data().rotation+90.0;
-    
-    let num_circles = (goal / radius).floor() as i32 -1;
-    
-    for i in 0..num_circles {
-      
-      let offset = Vector2::new(radius*(i+1) as f32*(math::to_radians(rotation)).cos(),
-                                radius*(i+1) as f32*(math::to_radians(rotation)).sin());
-      
-      laser_circles.push((offset, radius));
-      laser_circles.push((-offset, radius));
-    }
-    
-    laser_circles
+    Vec::new()
   }
   
+  fn colliders(&self) -> Vec<Collider> {
+    vec!(Collider::oriented_box(self.data().position, self.data().size, self.data().rotation))
+  }
   
   fn update(&mut self, delta_time: f32) {
     self.lifetime_decay(delta_time);
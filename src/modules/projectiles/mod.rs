@@ -9,13 +9,14 @@ mod aoe;
 mod laser_beam;
 
 use maat_graphics::DrawCall;
-use maat_graphics::math;
 
 use crate::modules::Animation;
 use crate::modules::entities::{BoxEntity, Hostility};
 use crate::modules::abilities::{BoxAbility};
+use crate::modules::colliders::{self, Collider};
+use crate::modules::damage::{Damage, DamageType};
 
-use crate::cgmath::{Vector2, Vector3, Vector4};
+use crate::cgmath::{Vector2, Vector3, Vector4, InnerSpace};
 
 use std::sync::Arc;
 
@@ -39,6 +40,12 @@ pub struct ProjectileData {
   lifetime_left: f32,
   should_exist: bool,
   passives: Vec<BoxAbility>,
+  // id of the entity that fired it, 0 if nothing did
+  owner: u32,
+  // where physics last moved it from, None until it first moves
+  previous_position: Option<Vector2<f32>>,
+  // pull from gravity wells this tick, used up by physics
+  gravity: Vector2<f32>,
 }
 
 impl ProjectileData {
@@ -57,6 +64,9 @@ impl ProjectileData {
       lifetime_left: 5.0,
       should_exist: true,
       passives: Vec::new(),
+      owner: 0,
+      previous_position: None,
+      gravity: Vector2::new(0.0, 0.0),
     }
   }
   
@@ -75,6 +85,9 @@ impl ProjectileData {
       lifetime_left: 5.0,
       should_exist: true,
       passives: Vec::new(),
+      owner: 0,
+      previous_position: None,
+      gravity: Vector2::new(0.0, 0.0),
     }
   }
   
@@ -148,10 +161,23 @@ pub trait Projectile: ProjectileClone {
     self.data().position
   }
   
+  fn previous_position(&self) -> Vector2<f32> {
+    self.data().previous_position.unwrap_or(self.data().position)
+  }
+  
+  // How far it moved during the last physics step
+  fn motion(&self) -> Vector2<f32> {
+    self.data().position - self.previous_position()
+  }
+  
   fn size(&self) -> Vector2<f32> {
     self.data().size
   }
   
+  fn velocity(&self) -> Vector2<f32> {
+    self.data().velocity
+  }
+  
   // Vec2<offset>, radius
   fn collision_information(&self) -> Vec<(Vector2<f32>, f32)>;
   
@@ -161,10 +187,21 @@ pub trait Projectile: ProjectileClone {
     self.data().damage
   }
   
+  // What kind of damage it does, which decides how much shields and hulls resist
+  fn damage_type(&self) -> DamageType;
+  
   fn should_exist(&self) -> bool {
     self.data().should_exist
   }
   
+  fn owner(&self) -> u32 {
+    self.data().owner
+  }
+  
+  fn set_owner(&mut self, id: u32) {
+    self.mut_data().owner = id;
+  }
+  
   fn hostile(&self) -> bool {
     self.data().hostility.is_hostile()
   }
@@ -185,10 +222,24 @@ pub trait Projectile: ProjectileClone {
     projectile_circles
   }
   
+  // What it collides as, the collision circles unless it declares a better fitting shape
+  fn colliders(&self) -> Vec<Collider> {
+    self.collision_circles().iter().map(|circle| Collider::circle(circle.truncate(), circle.z)).collect()
+  }
+  
+  fn pull(&mut self, acceleration: Vector2<f32>) {
+    self.mut_data().gravity += acceleration;
+  }
+  
   fn lock_hostility(&mut self) {
     self.mut_data().hostility_locked = true;
   }
   
+  // How far past its spawn position the projectile can still hit something before it expires
+  fn range(&self) -> f32 {
+    self.data().velocity.magnitude()*self.data().lifetime_left + self.data().size.x.max(self.data().size.y)*0.5
+  }
+  
   fn multiply_velocity(&mut self, factor: f32) {
     self.mut_data().velocity *= factor;
   }
@@ -224,53 +275,57 @@ pub trait Projectile: ProjectileClone {
     }
   }
   
-  fn collide_with(&mut self, entity: &mut BoxEntity) {
-    let entity_circles = entity.collision_circles();
-    let projectile_circles = self.collision_circles();
-    
-    let mut collided = false;
+  // Fraction of this tick's motion at which the projectile first touches the entity, with both
+  // swept from their previous positions so fast shots can't pass through small targets
+  fn time_of_impact(&self, entity: &BoxEntity) -> Option<f32> {
+    colliders::time_of_impact(&self.colliders(), self.motion(),
+                              &entity.colliders(), entity.motion())
+  }
+  
+  fn collide_with(&mut self, entity: &mut BoxEntity) -> Option<f32> {
+    let time = self.time_of_impact(entity);
     
-    for e_circle in entity_circles {
-      for p_circle in &projectile_circles {
-        if math::circle_collision(e_circle, *p_circle) {
-          if self.data().hostility.check_can_hurt(entity.hostility()) {
-            entity.hit(self.data().damage);
-            
-            let pos = self.data().position;
-            let vel = self.data().velocity;
-            
-            let hostility  = self.data().hostility.clone();
-            
-            for passive in &mut self.mut_data().passives {
-              passive.applied_to(entity, pos+vel, Vector2::new(0.0, 0.0), &hostility);
-            }
+    if let Some(time) = time {
+      if self.data().hostility.check_can_hurt(entity.hostility()) {
+        let pos = self.previous_position() + self.motion()*time;
+        let damage = Damage::new(self.data().damage, self.damage_type()).with_source(self.data().owner);
+        entity.hit_at(damage, pos);
+        
+        let vel = self.data().velocity;
+        
+        let hostility  = self.data().hostility.clone();
+        
+        for passive in &mut self.mut_data().passives {
+          passive.applied_to(entity, pos+vel, Vector2::new(0.0, 0.0), &hostility);
+          for effect in passive.status_effects() {
+            entity.activate_buff(effect.clone());
           }
-          
-          self.mut_data().should_exist = false;
-          
-          collided = true;
-          break;
         }
       }
-      if collided { break; }
+      
+      self.mut_data().should_exist = false;
     }
+    
+    time
   }
   
   fn physics(&mut self, delta_time: f32) {
     let velocity = self.data().velocity;
     let acceleration = self.data().acceleration;
+    self.mut_data().previous_position = Some(self.data().position);
     self.mut_data().position += velocity*delta_time;
     self.mut_data().velocity += acceleration*delta_time*delta_time;
+    let gravity = self.data().gravity;
+    self.mut_data().velocity += gravity*delta_time;
+    self.mut_data().gravity = Vector2::new(0.0, 0.0);
   }
-
+  
   fn draw(&self, draw_calls: &mut Vec<DrawCall>) {
     self.data().animation.draw(self.data().position, self.data().size, 
                                self.data().rotation, self.data().texture.to_string(), draw_calls);
   }
   
   fn draw_collision_circles(&self, draw_calls: &mut Vec<DrawCall>) {
-    let circles = self.collision_circles();
-    
     let colour = if self.data().hostility.is_hostile() {
       Vector4::new(1.0, 0.0, 0.0, 1.0)
     } else if self.data().hostility.is_friendly() {
@@ -279,8 +334,8 @@ pub trait Projectile: ProjectileClone {
       Vector4::new(0.0, 1.0, 0.0, 1.0)
     };
     
-    for circle in &circles {
-      draw_calls.push(DrawCall::draw_coloured(circle.xy(), Vector2::new(circle.z*2.0, circle.z*2.0), colour, 0.0));
+    for collider in &self.colliders() {
+      collider.draw(colour, draw_calls);
     }
   }
 }
@@ -0,0 +1,281 @@
+use crate::modules::player::{InputState, Refit};
+
+use crate::cgmath::Vector2;
+
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"ACWR";
+const VERSION: u8 = 3;
+
+pub const REPLAY_DIRECTORY: &str = "./replays";
+pub const LAST_REPLAY: &str = "./replays/last.replay";
+
+// Everything player::Input::update reads in a single tick.
+#[derive(Clone, PartialEq)]
+pub struct TickInput {
+  pub state: InputState,
+  pub bindings: [Option<i32>; 7],
+  pub refits: Vec<Refit>,
+  pub window_size: Vector2<f32>,
+}
+
+// Consecutive ticks with identical input are stored once with a repeat count,
+// holding a button down for a second costs one run instead of a thousand ticks.
+#[derive(Clone)]
+struct InputRun {
+  ticks: u32,
+  input: TickInput,
+}
+
+#[derive(Clone)]
+pub struct Replay {
+  seed: u64,
+  ship: String,
+  level: String,
+  window_size: Vector2<f32>,
+  runs: Vec<InputRun>,
+  playback_run: usize,
+  playback_tick: u32,
+}
+
+impl Replay {
+  pub fn new(seed: u64, ship: String, level: String, window_size: Vector2<f32>) -> Replay {
+    Replay {
+      seed,
+      ship,
+      level,
+      window_size,
+      runs: Vec::new(),
+      playback_run: 0,
+      playback_tick: 0,
+    }
+  }
+  
+  pub fn seed(&self) -> u64 {
+    self.seed
+  }
+  
+  pub fn ship(&self) -> String {
+    self.ship.to_string()
+  }
+  
+  pub fn level(&self) -> String {
+    self.level.to_string()
+  }
+  
+  pub fn window_size(&self) -> Vector2<f32> {
+    self.window_size
+  }
+  
+  pub fn total_ticks(&self) -> u64 {
+    self.runs.iter().map(|run| run.ticks as u64).sum()
+  }
+  
+  pub fn record(&mut self, input: TickInput) {
+    if let Some(run) = self.runs.last_mut() {
+      if run.input == input && run.ticks < u32::max_value() {
+        run.ticks += 1;
+        return;
+      }
+    }
+    
+    self.runs.push(InputRun { ticks: 1, input });
+  }
+  
+  pub fn finished(&self) -> bool {
+    self.playback_run >= self.runs.len()
+  }
+  
+  pub fn next_input(&mut self) -> Option<TickInput> {
+    if self.finished() {
+      return None;
+    }
+    
+    let input = self.runs[self.playback_run].input.clone();
+    
+    self.playback_tick += 1;
+    if self.playback_tick >= self.runs[self.playback_run].ticks {
+      self.playback_tick = 0;
+      self.playback_run += 1;
+    }
+    
+    Some(input)
+  }
+  
+  pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+    if let Some(parent) = path.as_ref().parent() {
+      fs::create_dir_all(parent)?;
+    }
+    
+    let mut bytes = Vec::with_capacity(32 + self.runs.len()*32);
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(VERSION);
+    bytes.extend_from_slice(&self.seed.to_le_bytes());
+    bytes.extend_from_slice(&self.window_size.x.to_bits().to_le_bytes());
+    bytes.extend_from_slice(&self.window_size.y.to_bits().to_le_bytes());
+    bytes.extend_from_slice(&(self.ship.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(self.ship.as_bytes());
+    bytes.extend_from_slice(&(self.level.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(self.level.as_bytes());
+    bytes.extend_from_slice(&(self.runs.len() as u32).to_le_bytes());
+    
+    for run in &self.runs {
+      let state = &run.input.state;
+      let buttons = [state.a_button_pressed, state.right_trigger_pressed, state.left_mouse,
+                     state.middle_mouse, state.right_mouse, state.q_pressed, state.w_pressed,
+                     state.e_pressed, state.r_pressed];
+      let mut flags: u16 = 0;
+      for i in 0..buttons.len() {
+        if buttons[i] {
+          flags |= 1 << i;
+        }
+      }
+      
+      bytes.extend_from_slice(&run.ticks.to_le_bytes());
+      bytes.extend_from_slice(&flags.to_le_bytes());
+      bytes.extend_from_slice(&state.mouse_pos.x.to_bits().to_le_bytes());
+      bytes.extend_from_slice(&state.mouse_pos.y.to_bits().to_le_bytes());
+      bytes.extend_from_slice(&state.left_stick_position.x.to_bits().to_le_bytes());
+      bytes.extend_from_slice(&state.left_stick_position.y.to_bits().to_le_bytes());
+      bytes.extend_from_slice(&run.input.window_size.x.to_bits().to_le_bytes());
+      bytes.extend_from_slice(&run.input.window_size.y.to_bits().to_le_bytes());
+      for binding in &run.input.bindings {
+        match binding {
+          Some(slot_id) => {
+            bytes.push(1);
+            bytes.extend_from_slice(&slot_id.to_le_bytes());
+          },
+          None => bytes.push(0),
+        }
+      }
+      bytes.extend_from_slice(&(run.input.refits.len() as u16).to_le_bytes());
+      for refit in &run.input.refits {
+        bytes.extend_from_slice(&(refit.section as u16).to_le_bytes());
+        bytes.extend_from_slice(&(refit.ability.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(refit.ability.as_bytes());
+      }
+    }
+    
+    let mut file = File::create(path)?;
+    file.write_all(&bytes)
+  }
+  
+  pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Replay> {
+    let mut bytes = Vec::new();
+    File::open(path)?.read_to_end(&mut bytes)?;
+    
+    let mut reader = ReplayReader { bytes: &bytes, position: 0 };
+    
+    if reader.take(4)? != MAGIC {
+      return Err(invalid_data("not a replay file"));
+    }
+    if reader.take(1)?[0] != VERSION {
+      return Err(invalid_data("unsupported replay version"));
+    }
+    
+    let seed = reader.read_u64()?;
+    let window_size = Vector2::new(reader.read_f32()?, reader.read_f32()?);
+    let ship = reader.read_string()?;
+    let level = reader.read_string()?;
+    
+    let num_runs = reader.read_u32()?;
+    let mut runs = Vec::with_capacity(num_runs as usize);
+    for _ in 0..num_runs {
+      let ticks = reader.read_u32()?;
+      let flags = reader.read_u16()?;
+      let mouse_pos = Vector2::new(reader.read_f32()?, reader.read_f32()?);
+      let left_stick_position = Vector2::new(reader.read_f32()?, reader.read_f32()?);
+      let tick_window_size = Vector2::new(reader.read_f32()?, reader.read_f32()?);
+      let mut bindings = [None; 7];
+      for binding in &mut bindings {
+        if reader.take(1)?[0] != 0 {
+          *binding = Some(reader.read_i32()?);
+        }
+      }
+      let num_refits = reader.read_u16()?;
+      let mut refits = Vec::with_capacity(num_refits as usize);
+      for _ in 0..num_refits {
+        let section = reader.read_u16()? as usize;
+        let ability = reader.read_string()?;
+        refits.push(Refit { section, ability });
+      }
+      
+      let pressed = |i: u16| flags & (1 << i) != 0;
+      let state = InputState {
+        left_stick_position,
+        a_button_pressed: pressed(0),
+        right_trigger_pressed: pressed(1),
+        mouse_pos,
+        left_mouse: pressed(2),
+        middle_mouse: pressed(3),
+        right_mouse: pressed(4),
+        q_pressed: pressed(5),
+        w_pressed: pressed(6),
+        e_pressed: pressed(7),
+        r_pressed: pressed(8),
+      };
+      
+      runs.push(InputRun { ticks, input: TickInput { state, bindings, refits, window_size: tick_window_size } });
+    }
+    
+    let mut replay = Replay::new(seed, ship, level, window_size);
+    replay.runs = runs;
+    
+    Ok(replay)
+  }
+}
+
+fn invalid_data(reason: &str) -> io::Error {
+  io::Error::new(io::ErrorKind::InvalidData, reason.to_string())
+}
+
+struct ReplayReader<'a> {
+  bytes: &'a [u8],
+  position: usize,
+}
+
+impl<'a> ReplayReader<'a> {
+  fn take(&mut self, length: usize) -> io::Result<&'a [u8]> {
+    if self.position + length > self.bytes.len() {
+      return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "replay file is truncated"));
+    }
+    
+    let slice = &self.bytes[self.position..self.position+length];
+    self.position += length;
+    
+    Ok(slice)
+  }
+  
+  fn read_u16(&mut self) -> io::Result<u16> {
+    let mut buffer = [0; 2];
+    buffer.copy_from_slice(self.take(2)?);
+    Ok(u16::from_le_bytes(buffer))
+  }
+  
+  fn read_u32(&mut self) -> io::Result<u32> {
+    let mut buffer = [0; 4];
+    buffer.copy_from_slice(self.take(4)?);
+    Ok(u32::from_le_bytes(buffer))
+  }
+  
+  fn read_i32(&mut self) -> io::Result<i32> {
+    Ok(self.read_u32()? as i32)
+  }
+  
+  fn read_u64(&mut self) -> io::Result<u64> {
+    let mut buffer = [0; 8];
+    buffer.copy_from_slice(self.take(8)?);
+    Ok(u64::from_le_bytes(buffer))
+  }
+  
+  fn read_f32(&mut self) -> io::Result<f32> {
+    Ok(f32::from_bits(self.read_u32()?))
+  }
+  
+  fn read_string(&mut self) -> io::Result<String> {
+    let length = self.read_u16()? as usize;
+    String::from_utf8(self.take(length)?.to_vec()).map_err(|_| invalid_data("string is not utf8"))
+  }
+}
@@ -5,11 +5,10 @@ use maat_graphics::imgui;
 use crate::modules::scenes::Scene;
 use crate::modules::scenes::SceneData;
 
-use crate::modules::buffs::{BoxBuff};
-use crate::modules::entities::{MutexEntity, BoxEntity, Ship};
-use crate::modules::projectiles::{MutexProjectile, BoxProjectile};
+use crate::modules::entities::{Ship, MutexEntity};
 use crate::modules::areas::{BoxArea, BenchmarkArea};
-use crate::modules::player;
+use crate::modules::player::InputState;
+use crate::modules::world::World;
 use crate::modules::ui::{BoxUi, PauseUi, AbilityUi, ShipModuleViewer};
 
 use crate::cgmath::{Vector2, Vector4};
@@ -20,11 +19,6 @@ use parking_lot::Mutex;
 use std::sync::Arc;
 
 use crate::modules::spatial_hash::SpatialHash;
-use crate::modules::collisions;
-use maat_graphics::ThreadPool;
-use std::thread;
-use std::sync::mpsc;
-use std::sync::mpsc::channel;
 
 use crate::modules::kdtree::Node;
 
@@ -42,54 +36,34 @@ impl UiIndex {
 
 pub struct BenchmarkScreen {
   data: SceneData,
-  areas: Vec<BoxArea>,
-  input: Arc<Mutex<player::Input>>,
-  ship: MutexEntity,
-  buffs: Vec<BoxBuff>,
-  projectiles: Vec<MutexProjectile>,
+  world: World,
   zoom: f32,
   camera: OrthoCamera,
   uis: Vec<BoxUi>,
   escape_pressed_last_frame: bool, 
   i_pressed_last_frame: bool, 
-  spatial_hash: Arc<Mutex<SpatialHash>>,
+  spatial_hash: Arc<Mutex<SpatialHash<MutexEntity>>>,
   collision_checks: u64,
   _kdtree: Option<Box<Node>>,
   total_delta_time: f32,
   virtual_input: Vec<f32>,
   all_fps: Vec<f64>,
   mouse_angle: f32,
-  thread_pool: ThreadPool,
-  tx: mpsc::Sender<()>,
-  rx: mpsc::Receiver<()>,
 }
 
 impl BenchmarkScreen {
   pub fn new(window_size: Vector2<f32>) -> BenchmarkScreen {
     let benchmark: BoxArea = Box::new(BenchmarkArea::new(Vector2::new(0.0, 0.0), Vector2::new(20000.0, 20000.0), 10));
     
-    let player_input = Arc::new(Mutex::new(player::Input::new()));
+    let world = World::new(vec!(benchmark), Box::new(Ship::new(Vector2::new(0.0, 0.0)).with_health(1500000000.0)), window_size);
     
-    let ship: MutexEntity = Arc::new(Mutex::new(Box::new(Ship::new(Vector2::new(0.0, 0.0)).with_health(1500000000.0))));
+    let ability_ui = AbilityUi::new(world.input(), window_size);
     
-    let ability_ui = AbilityUi::new(Arc::clone(&player_input), window_size);
-    
-    let mut module_viewer = ShipModuleViewer::new(window_size, &ship);
-    
-    let (tx, rx) = mpsc::channel();
-    let thread_pool = ThreadPool::new(5);
-    let fake_tx = tx.clone();
-     thread_pool.execute(move || {
-          fake_tx.send(()).unwrap();
-    });
+    let module_viewer = ShipModuleViewer::new(window_size, world.ship(), world.refits());
     
     BenchmarkScreen {
       data: SceneData::new(window_size, Vec::new()),
-      areas: vec!(benchmark),
-      input: player_input,
-      ship,
-      buffs: Vec::new(),
-      projectiles: Vec::new(),
+      world,
       zoom: 0.75,
       camera: OrthoCamera::new(window_size.x, window_size.y),
       uis: vec!(Box::new(ability_ui), Box::new(module_viewer), Box::new(PauseUi::new(window_size))),
@@ -102,21 +76,13 @@ impl BenchmarkScreen {
       virtual_input: (0..100).into_iter().map(|x| x as f32*0.5).collect::<Vec<f32>>(),
       all_fps: Vec::new(),
       mouse_angle: 0.0,
-      thread_pool,
-      tx,
-      rx,
     }
   }
   
-  pub fn recreate(window_size: Vector2<f32>, camera: OrthoCamera, areas: Vec<BoxArea>, ship: MutexEntity, input: Arc<Mutex<player::Input>>, buffs: Vec<BoxBuff>, projectiles: Vec<MutexProjectile>, zoom: f32, all_fps: Vec<f64>, mouse_angle: f32) -> BenchmarkScreen {
-    let (tx, rx) = mpsc::channel();
+  pub fn recreate(window_size: Vector2<f32>, camera: OrthoCamera, world: World, zoom: f32, all_fps: Vec<f64>, mouse_angle: f32) -> BenchmarkScreen {
     BenchmarkScreen {
       data: SceneData::new(window_size, Vec::new()), 
-      areas,
-      input,
-      ship,
-      buffs,
-      projectiles,
+      world,
       zoom,
       camera,
       uis: vec!(Box::new(PauseUi::new(window_size))),
@@ -129,9 +95,6 @@ impl BenchmarkScreen {
       virtual_input: (0..100).into_iter().map(|x| x as f32*0.5).collect::<Vec<f32>>(),
       all_fps,
       mouse_angle,
-      thread_pool: ThreadPool::new(5),
-      tx,
-      rx,
     }
   }
   
@@ -204,7 +167,7 @@ impl BenchmarkScreen {
       self.mut_data().should_close = true;
     }
       
-    if should_next_scene || { let ship = self.ship.lock(); !ship.should_exist() } {
+    if should_next_scene || self.world.ship_destroyed() {
       self.mut_data().next_scene = true;
     }
     
@@ -214,22 +177,7 @@ impl BenchmarkScreen {
     }
   }
   
-  pub fn update_player(&mut self, dim: Vector2<f32>, delta_time: f32) -> Vec<BoxProjectile> {
-    // Player
-    let left_stick_position =  self.data().controller.left_stick_position();
-    let xbox_a_button = self.data().controller.a_button_pressed();
-    let right_trigger_pressed = self.data().controller.right_trigger_pressed();
-    
-    let mouse_pos = self.data().mouse_pos;
-    let left_mouse = self.data.left_mouse;
-    let middle_mouse = self.data.middle_mouse;
-    let right_mouse = self.data.right_mouse;
-    
-    let q_pressed = self.data.keys.q_pressed();
-    let w_pressed = self.data.keys.w_pressed();
-    let e_pressed = self.data.keys.e_pressed();
-    let r_pressed = self.data.keys.r_pressed();
-    
+  pub fn virtual_input_state(&mut self, dim: Vector2<f32>, delta_time: f32) -> InputState {
     let mut mouse_pos = self.data.mouse_pos;
     
     self.total_delta_time += delta_time;
@@ -246,12 +194,10 @@ impl BenchmarkScreen {
       self.all_fps.sort_by(|a, b| a.partial_cmp(b).unwrap());
     }
     
-    let scroll_delta = self.data.scroll_delta;
     let mut left_mouse = self.data.left_mouse;
     let middle_mouse = self.data.middle_mouse;
     let mut right_mouse = self.data.right_mouse;
     let mut q_pressed = self.data.keys.q_pressed();
-    let escape_pressed = self.data.keys.escape_pressed() && !self.escape_pressed_last_frame;
     
     self.mouse_angle += 60.0*delta_time;
     
@@ -283,78 +229,23 @@ impl BenchmarkScreen {
       _ => {},
     }
     
-    
-    let mut ship = self.ship.lock();
-    let mut player_input = self.input.lock();
-    player_input.update(&mut *ship, left_stick_position, xbox_a_button, right_trigger_pressed, 
-                      mouse_pos, left_mouse, middle_mouse, right_mouse, q_pressed, w_pressed,
-                      e_pressed, r_pressed, dim, delta_time);
-    
-    let (buffs, new_projectiles) = ship.update(delta_time);
-    
-    let mut offset = 0;
-    for i in 0..self.buffs.len() {
-      self.buffs[i-offset].update(&mut *ship, delta_time);
-      if !self.buffs[i-offset].should_exist() {
-        self.buffs[i-offset].unapply_buff(&mut *ship);
-        self.buffs.remove(i-offset);
-        offset += 1;
-      }
-    }
-    
-    for buff in buffs {
-      buff.apply_buff(&mut *ship);
-      self.buffs.push(buff);
-    }
-    
-    new_projectiles
-  }
-  
-  pub fn update_areas(&mut self, dim: Vector2<f32>, delta_time: f32) -> Vec<BoxProjectile> {
-    let mut new_projectiles = Vec::new();
-    
-    let mut ship = self.ship.lock();
-    for area in &mut self.areas {
-      let projectiles = area.update(&mut *ship, dim, delta_time);
-      for projectile in projectiles {
-        new_projectiles.push(projectile);
-      }
-    }
-    
-    new_projectiles
-  }
-  
-  pub fn update_projectiles(&mut self, player_projectiles: Vec<BoxProjectile>, entity_projectiles: Vec<BoxProjectile>, delta_time: f32) {
-    // Projectiles 
-    for new_projectile in player_projectiles {
-      self.projectiles.push(Arc::new(Mutex::new(new_projectile)));
-    }
-    
-    for new_projectile in entity_projectiles {
-      self.projectiles.push(Arc::new(Mutex::new(new_projectile)));
-    }
-    
-    let mut offset = 0;
-    for i in 0..self.projectiles.len() {
-      if i < offset {
-        break;
-      }
-      
-      let projectile_should_exist;
-      {
-        let mut projectile = self.projectiles[i-offset].lock();
-        projectile.update(delta_time);
-        projectile_should_exist = projectile.should_exist();
-      }
-      if !projectile_should_exist {
-        self.projectiles.remove(i-offset);
-        offset += 1;
-      }
+    InputState {
+      left_stick_position: self.data().controller.left_stick_position(),
+      a_button_pressed: self.data().controller.a_button_pressed(),
+      right_trigger_pressed: self.data().controller.right_trigger_pressed(),
+      mouse_pos,
+      left_mouse,
+      middle_mouse,
+      right_mouse,
+      q_pressed,
+      w_pressed: self.data.keys.w_pressed(),
+      e_pressed: self.data.keys.e_pressed(),
+      r_pressed: self.data.keys.r_pressed(),
     }
   }
   
   pub fn update_camera(&mut self, dim: Vector2<f32>) {
-    let ship_pos = {let ship = self.ship.lock(); ship.position() };
+    let ship_pos = {let ship = self.world.ship().lock(); ship.position() };
     self.camera.window_resized(dim.x, dim.y);
     let camera_target = ship_pos*self.zoom - Vector2::new(dim.x*0.5, dim.y*0.5);
     self.camera.lerp_to_position(camera_target,  Vector2::new(0.05, 0.05));
@@ -552,7 +443,7 @@ impl Scene for BenchmarkScreen {
   }
   
   fn future_scene(&mut self, window_size: Vector2<f32>) -> Box<Scene> {
-    Box::new(BenchmarkScreen::recreate(window_size, self.camera.clone(), self.areas.clone(), self.ship.clone(), self.input.clone(), self.buffs.clone(), self.projectiles.clone(), self.zoom, self.all_fps.clone(), self.mouse_angle))
+    Box::new(BenchmarkScreen::recreate(window_size, self.camera.clone(), self.world.clone(), self.zoom, self.all_fps.clone(), self.mouse_angle))
   }
   
   fn update(&mut self, _ui: Option<&imgui::Ui>, _lua: Option<&mut Lua>, delta_time: f32) {
@@ -578,30 +469,9 @@ impl Scene for BenchmarkScreen {
     
     self.update_ui(dim, escape_pressed, i_pressed, delta_time);
     
-    let player_projectiles = self.update_player(dim, delta_time);
-    let entity_projectiles = self.update_areas(dim, delta_time);
-    self.update_projectiles(player_projectiles, entity_projectiles, delta_time);
-    
-    if self.rx.try_recv().is_ok() {
-      let mut entities: Vec<MutexEntity> = Vec::new();
-      let mut projectiles: Vec<MutexProjectile> = Vec::new();
-      for area in &self.areas {
-        for mutex_entity in &area.entities() {
-          entities.push(Arc::clone(&mutex_entity));
-        }
-      }
-      entities.push(Arc::clone(&self.ship));
-      
-      for i in 0..self.projectiles.len() {
-        projectiles.push(Arc::clone(&self.projectiles[i]));
-      }
-      
-      let tx = self.tx.clone();
-      self.thread_pool.execute(move || {
-        collisions::collisions(entities, projectiles);
-        tx.send(()).unwrap();
-      });
-    }
+    let input_state = self.virtual_input_state(dim, delta_time);
+    self.world.set_window_size(dim);
+    self.world.update(&input_state, None, delta_time);
   }
   
   fn draw(&self, draw_calls: &mut Vec<DrawCall>) {
@@ -625,16 +495,16 @@ impl Scene for BenchmarkScreen {
       }
     }
     
-    for mutex_projectile in &self.projectiles {
+    for mutex_projectile in self.world.projectiles() {
       let projectile = mutex_projectile.lock();
       projectile.draw(draw_calls);
     }
     
-    for area in &self.areas {
+    for area in self.world.areas() {
       area.draw(draw_calls);
     }
     
-    let ship = self.ship.lock();
+    let ship = self.world.ship().lock();
     ship.draw(draw_calls);
     
     draw_calls.push(DrawCall::draw_instanced("Astroid".to_string(), "Astroid".to_string()));
@@ -645,7 +515,7 @@ impl Scene for BenchmarkScreen {
     draw_calls.push(DrawCall::draw_instanced("LaserBeam".to_string(), "LaserBeam".to_string()));
     draw_calls.push(DrawCall::draw_instanced("Bulbz".to_string(), "Bulbz".to_string()));
     
-    for area in &self.areas {
+    for area in self.world.areas() {
       area.draw_ship_ui(draw_calls);
     }
     
@@ -5,7 +5,9 @@ use hlua::Lua;
 
 use crate::modules::scenes::Scene;
 use crate::modules::scenes::SceneData;
-use crate::modules::scenes::{ShipSelectScreen};
+use crate::modules::scenes::{ShipSelectScreen, ReplayScreen};
+use crate::modules::replay::Replay;
+use crate::modules::entities::{ShipDefinition, SHIP_DIRECTORY};
 use crate::cgmath::{Vector2, Vector4};
 
 const LOGO_TIMER: f32 = 1.5;
@@ -16,6 +18,7 @@ pub struct LoadScreen {
   logo_timer: f32,
   first_loop: bool,
   loop_num: u32,
+  replay: Option<Replay>,
 }
 
 impl LoadScreen {
@@ -26,8 +29,15 @@ impl LoadScreen {
       logo_timer: LOGO_TIMER,
       first_loop: true,
       loop_num: 0,
+      replay: None,
     }
   }
+  
+  // Skips ship selection and plays the replay once textures are loaded.
+  pub fn with_replay(mut self, replay: Replay) -> LoadScreen {
+    self.replay = Some(replay);
+    self
+  }
 }
 
 impl Scene for LoadScreen {
@@ -40,6 +50,10 @@ impl Scene for LoadScreen {
   }
   
   fn future_scene(&mut self, window_size: Vector2<f32>) -> Box<Scene> {
+    if let Some(replay) = self.replay.take() {
+      return Box::new(ReplayScreen::new(window_size, replay));
+    }
+    
     Box::new(ShipSelectScreen::new(window_size))
   }
   
@@ -67,6 +81,11 @@ impl Scene for LoadScreen {
       draw_calls.push(DrawCall::load_texture("Bulbz".to_string()));
       draw_calls.push(DrawCall::load_texture("Brew".to_string()));
       draw_calls.push(DrawCall::load_texture("BlueShield".to_string()));
+      for definition in ShipDefinition::load_all(SHIP_DIRECTORY) {
+        if definition.texture_path.is_some() {
+          draw_calls.push(DrawCall::load_texture(definition.texture.to_string()));
+        }
+      }
       // entities
       draw_calls.push(DrawCall::load_texture("Sun".to_string()));
       draw_calls.push(DrawCall::load_texture("Astroid".to_string()));
@@ -18,11 +18,13 @@ pub use self::load_screen::LoadScreen;
 pub use self::battle_screen::BattleScreen;
 pub use self::benchmark_screen::BenchmarkScreen;
 pub use self::ship_select_screen::ShipSelectScreen;
+pub use self::replay_screen::ReplayScreen;
 
 mod load_screen;
 mod battle_screen;
 mod benchmark_screen;
 mod ship_select_screen;
+mod replay_screen;
 
 pub struct ImGuiInfo {
   _wants_mouse: bool,
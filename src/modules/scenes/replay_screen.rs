@@ -0,0 +1,136 @@
+use maat_graphics::DrawCall;
+use maat_graphics::camera::OrthoCamera;
+use maat_graphics::imgui;
+
+use crate::modules::scenes::{Scene, SceneData, BattleScreen, ShipSelectScreen};
+
+use crate::modules::entities::{self, BoxEntity, Ship};
+use crate::modules::replay::Replay;
+use crate::modules::world::World;
+
+use crate::cgmath::{Vector2, Vector4};
+
+use hlua::Lua;
+
+// Plays a recorded battle back through the same World the BattleScreen runs,
+// feeding it the recorded input instead of the mouse and keyboard.
+pub struct ReplayScreen {
+  data: SceneData,
+  world: World,
+  replay: Replay,
+  zoom: f32,
+  camera: OrthoCamera,
+  escape_pressed_last_frame: bool,
+}
+
+impl ReplayScreen {
+  pub fn new(window_size: Vector2<f32>, replay: Replay) -> ReplayScreen {
+    let ship: BoxEntity = match entities::selectable_ship(&replay.ship()) {
+      Some(ship) => ship,
+      None => {
+        println!("Replay ship {} is not selectable, using the default ship", replay.ship());
+        Box::new(Ship::new(Vector2::new(0.0, 0.0)))
+      }
+    };
+    
+    let world = match World::battle(&replay.level(), ship.clone(), replay.window_size()) {
+      Ok(world) => world,
+      Err(e) => {
+        println!("Failed to load replay level {}: {}", replay.level(), e);
+        World::new(Vec::new(), ship, replay.window_size())
+      }
+    };
+    let world = world.with_seed(replay.seed()).deterministic();
+    
+    ReplayScreen {
+      data: SceneData::new(window_size, Vec::new()),
+      world,
+      replay,
+      zoom: 0.75,
+      camera: OrthoCamera::new(window_size.x, window_size.y),
+      escape_pressed_last_frame: false,
+    }
+  }
+  
+  pub fn recreate(window_size: Vector2<f32>, camera: OrthoCamera, world: World, replay: Replay, zoom: f32) -> ReplayScreen {
+    ReplayScreen {
+      data: SceneData::new(window_size, Vec::new()),
+      world,
+      replay,
+      zoom,
+      camera,
+      escape_pressed_last_frame: false,
+    }
+  }
+  
+  pub fn update_camera(&mut self, dim: Vector2<f32>) {
+    let ship_pos = {let ship = self.world.ship().lock(); ship.position() };
+    self.camera.window_resized(dim.x, dim.y);
+    let camera_target = ship_pos*self.zoom - Vector2::new(dim.x*0.5, dim.y*0.5);
+    self.camera.lerp_to_position(camera_target,  Vector2::new(0.05, 0.05));
+  }
+}
+
+impl Scene for ReplayScreen {
+  fn data(&self) -> &SceneData {
+    &self.data
+  }
+  
+  fn mut_data(&mut self) -> &mut SceneData {
+    &mut self.data
+  }
+  
+  fn future_scene(&mut self, window_size: Vector2<f32>) -> Box<Scene> {
+    if self.data().window_resized {
+      Box::new(ReplayScreen::recreate(window_size, self.camera.clone(), self.world.clone(), self.replay.clone(), self.zoom))
+    } else {
+      Box::new(ShipSelectScreen::new(window_size))
+    }
+  }
+  
+  fn update(&mut self, _ui: Option<&imgui::Ui>, lua: Option<&mut Lua>, delta_time: f32) {
+    let dim = self.data().window_dim;
+    
+    let escape_pressed = self.data.keys.escape_pressed() && !self.escape_pressed_last_frame;
+    self.escape_pressed_last_frame = self.data().keys.escape_pressed();
+    
+    self.update_camera(dim);
+    
+    if escape_pressed {
+      self.mut_data().next_scene = true;
+      return;
+    }
+    
+    match self.replay.next_input() {
+      Some(tick) => {
+        self.world.replay_tick(&tick, lua, delta_time);
+      },
+      None => {
+        self.mut_data().next_scene = true;
+      }
+    }
+  }
+  
+  fn draw(&self, draw_calls: &mut Vec<DrawCall>) {
+    let dim = self.data().window_dim;
+    
+    draw_calls.push(DrawCall::set_texture_scale(self.zoom));
+    
+    draw_calls.push(DrawCall::replace_ortho_camera(self.camera.clone()));
+    
+    BattleScreen::draw_world(&self.world, draw_calls);
+    
+    draw_calls.push(DrawCall::set_texture_scale(1.0));
+    draw_calls.push(DrawCall::reset_ortho_camera());
+    
+    draw_calls.push(DrawCall::draw_text_basic(Vector2::new(32.0, dim.y-96.0),
+                                              Vector2::new(64.0, 64.0),
+                                              Vector4::new(1.0, 1.0, 1.0, 1.0),
+                                              "Replay: ".to_string() + &self.world.ticks().to_string() + " / " + &self.replay.total_ticks().to_string(),
+                                              "Arial".to_string()));
+    
+    draw_calls.push(DrawCall::set_texture_scale(self.zoom));
+    
+    draw_calls.push(DrawCall::replace_ortho_camera(self.camera.clone()));
+  }
+}
@@ -7,7 +7,8 @@ use crate::modules::scenes::SceneData;
 use crate::modules::scenes::{BattleScreen, BenchmarkScreen};
 
 use crate::modules::ui::{BoxUi, ShipSelectUi};
-use crate::modules::entities::{Entity, BoxEntity, Astroid, Brew, Ship, Sun};
+use crate::modules::entities::{self, BoxEntity};
+use crate::modules::areas::DEFAULT_LEVEL;
 
 use crate::cgmath::{Vector2};
 
@@ -20,14 +21,7 @@ pub struct ShipSelectScreen {
 
 impl ShipSelectScreen {
   pub fn new(window_size: Vector2<f32>) -> ShipSelectScreen {
-    let position = Vector2::new(0.0, 0.0);
-    let size = Vector2::new(300.0, 300.0);
-    let possible_ships: Vec<BoxEntity> = vec!(
-      Box::new(Astroid::new(position, size)),
-      Box::new(Sun::new(position)),
-      Box::new(Brew::new(position)),
-      Box::new(Ship::new(position)),
-    );
+    let possible_ships = entities::selectable_ships();
     
     let mut textures = Vec::new();
     for ship in &possible_ships {
@@ -54,7 +48,7 @@ impl Scene for ShipSelectScreen {
   
   fn future_scene(&mut self, window_size: Vector2<f32>) -> Box<Scene> {
     let index = self.select_ui.external_option_value() as usize;
-    Box::new(BattleScreen::new(window_size, self.possible_ships[index].clone()))
+    Box::new(BattleScreen::new(window_size, self.possible_ships[index].clone(), DEFAULT_LEVEL))
    // Box::new(BenchmarkScreen::new(window_size))
   }
   
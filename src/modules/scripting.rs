@@ -0,0 +1,258 @@
+use crate::modules::entities::Hostility;
+
+use crate::cgmath::Vector2;
+
+use hlua::{self, Lua};
+
+use parking_lot::Mutex;
+
+use rand::Rng;
+use rand::rngs::StdRng;
+
+use std::sync::Arc;
+
+pub const SCRIPT_DIRECTORY: &str = "./resources/scripts";
+
+#[derive(Clone)]
+pub struct EntitySnapshot {
+  pub id: u32,
+  // None for the player ship
+  pub area: Option<usize>,
+  pub position: Vector2<f32>,
+  pub velocity: Vector2<f32>,
+  pub rotation: f32,
+  pub health: f32,
+  pub max_health: f32,
+  pub hostility: Hostility,
+  pub last_attacker: Option<u32>,
+}
+
+#[derive(Clone)]
+pub enum ScriptCommand {
+  Spawn {
+    id: u32,
+    area: usize,
+    kind: String,
+    position: Vector2<f32>,
+    hostility: String,
+    controller: String,
+    abilities: Vec<String>,
+  },
+  ApplyBuff {
+    id: u32,
+    buff: String,
+  },
+  FireAbility {
+    id: u32,
+    ability: String,
+    target: Vector2<f32>,
+  },
+  AreaCallback {
+    area: usize,
+    function: String,
+  },
+}
+
+// What scripts can see of the world this tick and what they asked it to do. The World
+// fills in the snapshot before any area callbacks run and applies the commands afterwards,
+// so scripts never hold a lock on a live entity.
+pub struct ScriptState {
+  pub tick: u64,
+  pub player_id: u32,
+  pub next_id: u32,
+  pub num_areas: usize,
+  pub entities: Vec<EntitySnapshot>,
+  pub commands: Vec<ScriptCommand>,
+}
+
+impl ScriptState {
+  pub fn new() -> ScriptState {
+    ScriptState {
+      tick: 0,
+      player_id: 0,
+      next_id: 1,
+      num_areas: 0,
+      entities: Vec::new(),
+      commands: Vec::new(),
+    }
+  }
+  
+  pub fn entity(&self, id: u32) -> Option<&EntitySnapshot> {
+    self.entities.iter().find(|entity| entity.id == id)
+  }
+}
+
+#[derive(Clone)]
+pub struct ScriptApi {
+  state: Arc<Mutex<ScriptState>>,
+  // the world's rng, so what scripts roll follows the battle's seed
+  rng: Arc<Mutex<StdRng>>,
+}
+
+impl ScriptApi {
+  pub fn new(rng: Arc<Mutex<StdRng>>) -> ScriptApi {
+    ScriptApi {
+      state: Arc::new(Mutex::new(ScriptState::new())),
+      rng,
+    }
+  }
+  
+  pub fn state(&self) -> &Arc<Mutex<ScriptState>> {
+    &self.state
+  }
+  
+  pub fn is_same(&self, other: &ScriptApi) -> bool {
+    Arc::ptr_eq(&self.state, &other.state)
+  }
+  
+  pub fn take_commands(&self) -> Vec<ScriptCommand> {
+    let mut state = self.state.lock();
+    state.commands.drain(..).collect()
+  }
+  
+  // Functions scripts can call, every one of them only reads the snapshot or queues a command.
+  pub fn register(&self, lua: &mut Lua) {
+    let state = Arc::clone(&self.state);
+    lua.set("tick", hlua::function0(move || -> u32 {
+      state.lock().tick as u32
+    }));
+    
+    let state = Arc::clone(&self.state);
+    lua.set("player_id", hlua::function0(move || -> u32 {
+      state.lock().player_id
+    }));
+    
+    let state = Arc::clone(&self.state);
+    lua.set("area_count", hlua::function0(move || -> u32 {
+      state.lock().num_areas as u32
+    }));
+    
+    let state = Arc::clone(&self.state);
+    lua.set("area_entities", hlua::function1(move |area: u32| -> Vec<u32> {
+      let state = state.lock();
+      state.entities.iter().filter(|entity| entity.area == Some(area as usize)).map(|entity| entity.id).collect()
+    }));
+    
+    let state = Arc::clone(&self.state);
+    lua.set("entity_exists", hlua::function1(move |id: u32| -> bool {
+      state.lock().entity(id).is_some()
+    }));
+    
+    let state = Arc::clone(&self.state);
+    lua.set("entity_position", hlua::function1(move |id: u32| -> (f32, f32) {
+      state.lock().entity(id).map(|entity| (entity.position.x, entity.position.y)).unwrap_or((0.0, 0.0))
+    }));
+    
+    let state = Arc::clone(&self.state);
+    lua.set("entity_velocity", hlua::function1(move |id: u32| -> (f32, f32) {
+      state.lock().entity(id).map(|entity| (entity.velocity.x, entity.velocity.y)).unwrap_or((0.0, 0.0))
+    }));
+    
+    let state = Arc::clone(&self.state);
+    lua.set("entity_rotation", hlua::function1(move |id: u32| -> f32 {
+      state.lock().entity(id).map(|entity| entity.rotation).unwrap_or(0.0)
+    }));
+    
+    let state = Arc::clone(&self.state);
+    lua.set("entity_health", hlua::function1(move |id: u32| -> (f32, f32) {
+      state.lock().entity(id).map(|entity| (entity.health, entity.max_health)).unwrap_or((0.0, 0.0))
+    }));
+    
+    let state = Arc::clone(&self.state);
+    lua.set("entity_hostility", hlua::function1(move |id: u32| -> String {
+      state.lock().entity(id).map(|entity| hostility_name(&entity.hostility).to_string()).unwrap_or("".to_string())
+    }));
+    
+    self.register_random(lua);
+    
+    // spawn_entity(area, kind, x, y, hostility, controller, abilities) where x and y are relative
+    // to the area and abilities is a comma separated list, returns the id the entity will have.
+    let state = Arc::clone(&self.state);
+    lua.set("spawn_entity", hlua::function7(move |area: u32, kind: String, x: f32, y: f32, hostility: String, controller: String, abilities: String| -> u32 {
+      let mut state = state.lock();
+      let id = state.next_id;
+      state.next_id += 1;
+      
+      let abilities = abilities.split(',').map(|a| a.trim().to_string()).filter(|a| !a.is_empty()).collect();
+      state.commands.push(ScriptCommand::Spawn {
+        id,
+        area: area as usize,
+        kind,
+        position: Vector2::new(x, y),
+        hostility,
+        controller,
+        abilities,
+      });
+      
+      id
+    }));
+    
+    let state = Arc::clone(&self.state);
+    lua.set("apply_buff", hlua::function2(move |id: u32, buff: String| {
+      state.lock().commands.push(ScriptCommand::ApplyBuff { id, buff });
+    }));
+    
+    let state = Arc::clone(&self.state);
+    lua.set("fire_ability", hlua::function4(move |id: u32, ability: String, x: f32, y: f32| {
+      state.lock().commands.push(ScriptCommand::FireAbility { id, ability, target: Vector2::new(x, y) });
+    }));
+    
+    // on_area_tick(area, "function_name"), the named global is called with the delta time
+    // every tick from that area's update_area.
+    let state = Arc::clone(&self.state);
+    lua.set("on_area_tick", hlua::function2(move |area: u32, function: String| {
+      state.lock().commands.push(ScriptCommand::AreaCallback { area: area as usize, function });
+    }));
+  }
+  
+  // random(min, max), seeded from the world, and math.random replaced with one drawing from it.
+  // The unseeded math.random would break deterministic worlds and replays. Controller scripts
+  // run in their own Lua state and get these too.
+  pub fn register_random(&self, lua: &mut Lua) {
+    let rng = Arc::clone(&self.rng);
+    lua.set("random", hlua::function2(move |min: f32, max: f32| -> f32 {
+      if max <= min {
+        return min;
+      }
+      rng.lock().gen_range(min, max)
+    }));
+    
+    let rng = Arc::clone(&self.rng);
+    lua.set("random_int", hlua::function2(move |min: i32, max: i32| -> i32 {
+      if max < min {
+        return min;
+      }
+      rng.lock().gen_range::<i64>(min as i64, max as i64 + 1) as i32
+    }));
+    
+    let seeded_math_random = "
+      math.random = function(m, n)
+        if m == nil then return random(0, 1) end
+        if n == nil then m, n = 1, m end
+        return random_int(m, n)
+      end
+      math.randomseed = function() end";
+    if let Err(e) = lua.execute::<()>(seeded_math_random) {
+      println!("Failed to replace math.random: {:?}", e);
+    }
+  }
+}
+
+pub fn hostility_name(hostility: &Hostility) -> &'static str {
+  match hostility {
+    Hostility::Hostile => "Hostile",
+    Hostility::Neutral => "Neutral",
+    Hostility::Friendly => "Friendly",
+    Hostility::Misc => "Misc",
+  }
+}
+
+pub fn parse_hostility(hostility: &str) -> Option<Hostility> {
+  match hostility {
+    "Hostile" => Some(Hostility::Hostile),
+    "Neutral" => Some(Hostility::Neutral),
+    "Friendly" => Some(Hostility::Friendly),
+    "Misc" => Some(Hostility::Misc),
+    _ => None,
+  }
+}
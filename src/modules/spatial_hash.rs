@@ -1,10 +1,158 @@
-use crate::modules::entities::{MutexEntity};
-use crate::modules::projectiles::{BoxProjectile};
+use crate::modules::entities::{MutexEntity, BoxEntity};
+use crate::modules::projectiles::{BoxProjectile, MutexProjectile};
+use crate::modules::colliders::colliders_bounds;
 
-use crate::cgmath::{Vector2,Vector4};
+use crate::cgmath::Vector2;
 
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+// Past this many cells the empty ones are dropped on clear, so a swarm passing through
+// doesn't leave the map holding every cell it ever touched.
+const MAX_CELLS: usize = 65536;
+
+// Anything with a bounding box that can go in, or be looked up in, a SpatialHash
+pub trait SpatialObject {
+  // min and max corners
+  fn bounds(&self) -> (Vector2<f32>, Vector2<f32>);
+}
+
+impl SpatialObject for BoxEntity {
+  fn bounds(&self) -> (Vector2<f32>, Vector2<f32>) {
+    colliders_bounds(&self.colliders(), self.position(), self.motion())
+  }
+}
+
+impl SpatialObject for BoxProjectile {
+  fn bounds(&self) -> (Vector2<f32>, Vector2<f32>) {
+    colliders_bounds(&self.colliders(), self.position(), self.motion())
+  }
+}
+
+impl SpatialObject for MutexEntity {
+  fn bounds(&self) -> (Vector2<f32>, Vector2<f32>) {
+    self.lock().bounds()
+  }
+}
+
+impl SpatialObject for MutexProjectile {
+  fn bounds(&self) -> (Vector2<f32>, Vector2<f32>) {
+    self.lock().bounds()
+  }
+}
+
+// Buckets objects by every grid cell their bounds touch. Objects and cells are kept between
+// frames and only emptied on clear, so a hash reused every tick stops allocating once it has
+// warmed up. Lookups return each object once, in the order it was inserted.
+#[derive(Clone)]
+pub struct SpatialHash<T> {
+  cell_size: f32,
+  objects: Vec<T>,
+  // indices into objects
+  cells: HashMap<(i32, i32), Vec<usize>>,
+  // cells with something in them, in the order they were first filled
+  occupied: Vec<(i32, i32)>,
+}
+
+impl<T: Clone> SpatialHash<T> {
+  pub fn new(cell_size: f32) -> SpatialHash<T> {
+    SpatialHash {
+      cell_size,
+      objects: Vec::new(),
+      cells: HashMap::new(),
+      occupied: Vec::new(),
+    }
+  }
+  
+  pub fn clear(&mut self) {
+    self.objects.clear();
+    for index in self.occupied.drain(..) {
+      if let Some(cell) = self.cells.get_mut(&index) {
+        cell.clear();
+      }
+    }
+    
+    if self.cells.len() > MAX_CELLS {
+      self.cells.clear();
+    }
+  }
+  
+  pub fn len(&self) -> usize {
+    self.objects.len()
+  }
+  
+  // Floored so cells either side of zero are the same size
+  fn hash(&self, point: Vector2<f32>) -> (i32, i32) {
+    ((point.x/self.cell_size).floor() as i32, (point.y/self.cell_size).floor() as i32)
+  }
+  
+  pub fn insert(&mut self, object: T, min: Vector2<f32>, max: Vector2<f32>) {
+    let index = self.objects.len();
+    self.objects.push(object);
+    
+    let min_hash = self.hash(min);
+    let max_hash = self.hash(max);
+    for i in min_hash.0..=max_hash.0 {
+      for j in min_hash.1..=max_hash.1 {
+        let cell = self.cells.entry((i,j)).or_insert_with(Vec::new);
+        if cell.is_empty() {
+          self.occupied.push((i,j));
+        }
+        cell.push(index);
+      }
+    }
+  }
+  
+  // Everything in the cells the box touches
+  pub fn retrieve(&self, min: Vector2<f32>, max: Vector2<f32>) -> Vec<T> {
+    let mut indices = Vec::new();
+    
+    let min_hash = self.hash(min);
+    let max_hash = self.hash(max);
+    for i in min_hash.0..=max_hash.0 {
+      for j in min_hash.1..=max_hash.1 {
+        if let Some(cell) = self.cells.get(&(i,j)) {
+          indices.extend(cell.iter().cloned());
+        }
+      }
+    }
+    indices.sort();
+    indices.dedup();
+    
+    indices.into_iter().map(|index| self.objects[index].clone()).collect()
+  }
+  
+  // Every pair of objects that share at least one cell
+  pub fn retrieve_possible_collisions(&self) -> Vec<(T, T)> {
+    let mut seen = HashSet::new();
+    let mut pairs = Vec::new();
+    for index in &self.occupied {
+      let cell = &self.cells[index];
+      for i in 0..cell.len() {
+        for j in i+1..cell.len() {
+          if seen.insert((cell[i], cell[j])) {
+            pairs.push((self.objects[cell[i]].clone(), self.objects[cell[j]].clone()));
+          }
+        }
+      }
+    }
+    
+    pairs
+  }
+}
+
+impl<T: Clone + SpatialObject> SpatialHash<T> {
+  pub fn insert_object_for_point(&mut self, object: T) {
+    let (min, max) = object.bounds();
+    self.insert(object, min, max);
+  }
+  
+  pub fn retrieve_objects<O: SpatialObject>(&self, object: &O) -> Vec<T> {
+    let (min, max) = object.bounds();
+    self.retrieve(min, max)
+  }
+}
+
 struct Content {
   pub index: (i32, i32),
   pub objects: Vec<MutexEntity>,
@@ -83,14 +231,16 @@ impl ObjectContainer {
   }
 }
 
-pub struct SpatialHash {
+// The original container that scans every cell on each insert and lookup, only kept so
+// `headless spatial-hash` has something to compare SpatialHash against.
+pub struct LinearSpatialHash {
   cell_size: f32,
   contents: ObjectContainer,
 }
 
-impl SpatialHash {
-  pub fn new(cell_size: f32) -> SpatialHash {
-    SpatialHash {
+impl LinearSpatialHash {
+  pub fn new(cell_size: f32) -> LinearSpatialHash {
+    LinearSpatialHash {
       cell_size,
       contents: ObjectContainer::new(),
     }
@@ -101,19 +251,15 @@ impl SpatialHash {
   }
   
   fn hash(&self, point: Vector2<f32>) -> (i32, i32) {
-    ((point.x/self.cell_size) as i32, (point.y/self.cell_size) as i32)
+    ((point.x/self.cell_size).floor() as i32, (point.y/self.cell_size).floor() as i32)
   }
   
   pub fn insert_object_for_point(&mut self, mutex_object: MutexEntity) {
-    let object = mutex_object.lock();
-    let position = object.position();
-    let offset = object.size().x.max(object.size().y)*0.5;
-    let min = position - object.size()*0.5;//Vector2::new(offset, offset);
-    let max = position + object.size()*0.5;//Vector2::new(offset, offset);
+    let (min, max) = mutex_object.bounds();
     let min_hash = self.hash(min);
     let max_hash = self.hash(max);
-    for i in min_hash.0..max_hash.0 {
-      for j in min_hash.1..max_hash.1 {
+    for i in min_hash.0..=max_hash.0 {
+      for j in min_hash.1..=max_hash.1 {
         self.contents.insert_object((i,j), Arc::clone(&mutex_object));
       }
     }
@@ -122,14 +268,11 @@ impl SpatialHash {
   pub fn retrieve_objects(&self, object: &BoxProjectile) -> Vec<MutexEntity> {
     let mut objects = Vec::new();
     
-    let position = object.position();
-    let offset = object.size().x.max(object.size().y)*0.5;
-    let min = position - Vector2::new(offset, offset);
-    let max = position + Vector2::new(offset, offset);
+    let (min, max) = object.bounds();
     let min_hash = self.hash(min);
     let max_hash = self.hash(max);
-    for i in min_hash.0..max_hash.0 {
-      for j in min_hash.1..max_hash.1 {
+    for i in min_hash.0..=max_hash.0 {
+      for j in min_hash.1..=max_hash.1 {
         for object in self.contents.retrieve_objects((i,j)) {
           objects.push(Arc::clone(&object));
         }
@@ -0,0 +1,181 @@
+// Stats buffs can change. Entities keep the base value of each apart from the effective value,
+// which is worked out again from the base whenever the modifiers on it change, so buffs coming
+// and going in any order can't leave a stat drifted.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stat {
+  MaxVelocity,
+  MaxAngularVelocity,
+  HealthRegen,
+  ShieldRegen,
+  EnergyRegen,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Modifier {
+  Add(f32),
+  Multiply(f32),
+  // replaces whatever the other modifiers came to
+  Override(f32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StatModifier {
+  stat: Stat,
+  modifier: Modifier,
+}
+
+impl StatModifier {
+  pub fn add(stat: Stat, amount: f32) -> StatModifier {
+    StatModifier {
+      stat,
+      modifier: Modifier::Add(amount),
+    }
+  }
+  
+  pub fn multiply(stat: Stat, factor: f32) -> StatModifier {
+    StatModifier {
+      stat,
+      modifier: Modifier::Multiply(factor),
+    }
+  }
+  
+  pub fn override_with(stat: Stat, value: f32) -> StatModifier {
+    StatModifier {
+      stat,
+      modifier: Modifier::Override(value),
+    }
+  }
+  
+  pub fn stat(&self) -> Stat {
+    self.stat
+  }
+  
+  pub fn modifier(&self) -> Modifier {
+    self.modifier
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+  pub max_velocity: f32,
+  // degrees per second
+  pub max_angular_velocity: f32,
+  // per second
+  pub health_regen: f32,
+  pub shield_regen: f32,
+  // per second, on top of what reactors generate
+  pub energy_regen: f32,
+}
+
+impl Stats {
+  pub fn get(&self, stat: Stat) -> f32 {
+    match stat {
+      Stat::MaxVelocity => self.max_velocity,
+      Stat::MaxAngularVelocity => self.max_angular_velocity,
+      Stat::HealthRegen => self.health_regen,
+      Stat::ShieldRegen => self.shield_regen,
+      Stat::EnergyRegen => self.energy_regen,
+    }
+  }
+  
+  pub fn set(&mut self, stat: Stat, value: f32) {
+    match stat {
+      Stat::MaxVelocity => self.max_velocity = value,
+      Stat::MaxAngularVelocity => self.max_angular_velocity = value,
+      Stat::HealthRegen => self.health_regen = value,
+      Stat::ShieldRegen => self.shield_regen = value,
+      Stat::EnergyRegen => self.energy_regen = value,
+    }
+  }
+  
+  // These stats with every modifier applied. Each stat becomes (base + additions) * factors,
+  // unless something overrides it, so the order the modifiers come in doesn't matter. With
+  // more than one override the lowest wins.
+  pub fn modified(&self, modifiers: &[StatModifier]) -> Stats {
+    let mut stats = *self;
+    for &stat in &[Stat::MaxVelocity, Stat::MaxAngularVelocity, Stat::HealthRegen, Stat::ShieldRegen, Stat::EnergyRegen] {
+      let mut added = 0.0;
+      let mut factor = 1.0;
+      let mut overridden: Option<f32> = None;
+      for modifier in modifiers.iter().filter(|modifier| modifier.stat() == stat) {
+        match modifier.modifier() {
+          Modifier::Add(amount) => added += amount,
+          Modifier::Multiply(multiplier) => factor *= multiplier,
+          Modifier::Override(value) => overridden = Some(overridden.map_or(value, |lowest| lowest.min(value))),
+        }
+      }
+      
+      let value = overridden.unwrap_or((self.get(stat) + added)*factor);
+      stats.set(stat, value);
+    }
+    
+    stats
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  
+  fn base() -> Stats {
+    Stats {
+      max_velocity: 800.0,
+      max_angular_velocity: 540.0,
+      health_regen: 0.0,
+      shield_regen: 5.0,
+      energy_regen: 20.0,
+    }
+  }
+  
+  // Every ordering of 0..n
+  fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+      return vec!(Vec::new());
+    }
+    
+    let mut orders = Vec::new();
+    for order in permutations(n-1) {
+      for i in 0..n {
+        let mut order = order.clone();
+        order.insert(i, n-1);
+        orders.push(order);
+      }
+    }
+    
+    orders
+  }
+  
+  #[test]
+  fn modifier_order_does_not_matter() {
+    let base = base();
+    let modifiers = vec!(
+      StatModifier::add(Stat::MaxVelocity, 100.0),
+      StatModifier::multiply(Stat::MaxVelocity, 2.0),
+      StatModifier::multiply(Stat::MaxVelocity, 0.25),
+      StatModifier::add(Stat::ShieldRegen, 3.0),
+      StatModifier::override_with(Stat::MaxAngularVelocity, 90.0),
+    );
+    
+    for order in permutations(modifiers.len()) {
+      let ordered: Vec<StatModifier> = order.iter().map(|&i| modifiers[i]).collect();
+      let stats = base.modified(&ordered);
+      assert!((stats.max_velocity - (base.max_velocity + 100.0)*0.5).abs() < 0.001, "order {:?} gave {:?}", order, stats);
+      assert!((stats.shield_regen - (base.shield_regen + 3.0)).abs() < 0.001, "order {:?} gave {:?}", order, stats);
+      assert_eq!(stats.max_angular_velocity, 90.0);
+      assert_eq!(stats.health_regen, base.health_regen);
+    }
+  }
+  
+  #[test]
+  fn lowest_override_wins() {
+    let modifiers = [StatModifier::override_with(Stat::MaxVelocity, 300.0),
+                     StatModifier::multiply(Stat::MaxVelocity, 2.0),
+                     StatModifier::override_with(Stat::MaxVelocity, 100.0)];
+    assert_eq!(base().modified(&modifiers).max_velocity, 100.0);
+  }
+  
+  #[test]
+  fn no_modifiers_leaves_base() {
+    assert_eq!(base().modified(&[]), base());
+  }
+}
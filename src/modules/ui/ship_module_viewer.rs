@@ -2,12 +2,15 @@ use maat_graphics::DrawCall;
 use maat_gui::widgets::{Widget, Image, Button, Text, RadioButton};
 
 use crate::modules::ui::{Ui, UiData};
-use crate::modules::entities::{MutexEntity};
+use crate::modules::entities::MutexEntity;
 use crate::modules::entities::sections::ShipSection;
-use crate::modules::abilities::{BoxAbility, SingleShot, DoubleShot, Laser, Dash};
+use crate::modules::abilities::{self, BoxAbility};
+use crate::modules::player::Refit;
 
 use crate::cgmath::{Vector2, Vector4};
 
+use parking_lot::Mutex;
+
 use std::sync::Arc;
 /*
 enum UiIndex {
@@ -24,15 +27,23 @@ impl UiIndex {
 //const BACKGROUND: usize = 0;
 //const SHIP_VISUAL: usize =  1;
 const ABILITY_LIST: usize = 2;
+const SHIP_SECTIONS: usize = 3;
+
+// Abilities that can be picked from the list, by name
+const ABILITIES: [&str; 5] = ["SingleShot", "DoubleShot", "Dash", "Laser", "NoAbility"];
 
+// Pick an ability from the list then click a section to install it there, picking the empty
+// ability clears the section's slots instead. Refits are queued for the World to apply on
+// its next tick rather than made to the ship here.
 #[derive(Clone)]
 pub struct ShipModuleViewer {
   data: UiData,
   mutex_ship: MutexEntity,
+  refits: Arc<Mutex<Vec<Refit>>>,
 }
 
 impl ShipModuleViewer {
-  pub fn new(window_size: Vector2<f32>, entity: &MutexEntity) -> ShipModuleViewer {
+  pub fn new(window_size: Vector2<f32>, entity: &MutexEntity, refits: Arc<Mutex<Vec<Refit>>>) -> ShipModuleViewer {
     let background_colour = Vector4::new(0.2, 0.2, 0.35, 1.0);
     let _button_colour = Vector4::new(0.8, 0.8, 0.2, 1.0);
     let ship_section_colour = Vector4::new(0.2, 0.2, 0.2, 1.0);
@@ -46,10 +57,10 @@ impl ShipModuleViewer {
     
     let mut ship_section_visuals = Vec::new();
     for ship_section in ship_sections {
-      ship_section_visuals.push(ShipModuleViewer::create_ship_section_image(window_size, ship_section_colour, ship_section));
+      ship_section_visuals.push(ShipModuleViewer::create_ship_section_button(window_size, ship_section_colour, ship_section));
     }
     
-    let abilities: Vec<BoxAbility> = vec!(Box::new(SingleShot::new()), Box::new(DoubleShot::new()), Box::new(Dash::new()), Box::new(Laser::new()));
+    let abilities: Vec<BoxAbility> = ABILITIES.iter().filter_map(|name| abilities::ability_by_name(name)).collect();
     let ability_list = ShipModuleViewer::create_ability_list_buttons(window_size, abilities);
     
     let mut ui_data = UiData::new()
@@ -64,6 +75,7 @@ impl ShipModuleViewer {
     ShipModuleViewer {
       data: ui_data,
       mutex_ship: Arc::clone(&entity),
+      refits,
     }
   }
   
@@ -101,14 +113,13 @@ impl ShipModuleViewer {
     ShipModuleViewer::ship_position(window_size)+offset
   }
   
-  fn create_ship_section_image(window_size: Vector2<f32>, primary_colour: Vector4<f32>, ship_section: &Box<ShipSection>) -> Box<Widget> {
+  fn create_ship_section_button(window_size: Vector2<f32>, primary_colour: Vector4<f32>, ship_section: &Box<ShipSection>) -> Box<Widget> {
     let pos = ShipModuleViewer::ship_section_position(window_size, ship_section.offset());
     let size = ship_section.size();
     
-    Box::new(Image::new(pos, size).with_primary_colour(primary_colour))
+    Box::new(Button::new(pos, size).with_primary_colour(primary_colour))
   }
   
-  
   fn create_button(_window_size: Vector2<f32>, primary_colour: Vector4<f32>, position: Vector2<f32>, size: Vector2<f32>, font: String, text: String) -> (Box<Widget>, Box<Widget>) {
     
     let button = Box::new(Button::new(position, size)
@@ -202,6 +213,17 @@ impl Ui for ShipModuleViewer {
       self.mut_data().widgets[i].set_position(new_positions[i]);
       self.mut_data().widgets[i].set_size(new_sizes[i]);
     }
+    
+    let selected = self.data().widgets[ABILITY_LIST].external_option_value();
+    if selected < 0 || selected as usize >= ABILITIES.len() {
+      return;
+    }
+    
+    for i in SHIP_SECTIONS..self.data().widgets.len() {
+      if self.data().widgets[i].pressed() {
+        self.refits.lock().push(Refit::new(i-SHIP_SECTIONS, ABILITIES[selected as usize]));
+      }
+    }
   }
   
   fn custom_draw(&self, _draw_calls: &mut Vec<DrawCall>) {
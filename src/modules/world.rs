@@ -0,0 +1,607 @@
+use maat_graphics::ThreadPool;
+
+use crate::modules::buffs::{self, BoxBuff};
+use crate::modules::entities::{MutexEntity, BoxEntity, ShipDefinition, SHIP_DIRECTORY};
+use crate::modules::projectiles::{BoxProjectile, MutexProjectile};
+use crate::modules::areas::{BoxArea, Level, EntityDefinition, EntityKind, ControllerKind, AbilityDefinition};
+use crate::modules::abilities;
+use crate::modules::controllers::TargetView;
+use crate::modules::scripting::{self, ScriptApi, ScriptCommand, EntitySnapshot, SCRIPT_DIRECTORY};
+use crate::modules::player::{self, InputState, Refit};
+use crate::modules::collisions;
+use crate::modules::broadphase::{BroadphaseKind, BoxBroadphase};
+use crate::modules::gravity::GravityField;
+use crate::modules::replay::{Replay, TickInput};
+
+use crate::cgmath::Vector2;
+
+use parking_lot::Mutex;
+
+use hlua::Lua;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::mpsc;
+
+pub const DEFAULT_SEED: u64 = 645;
+
+// Everything that ticks during a battle, with no window, camera or draw calls attached.
+// Scenes wrap a World for rendering, the headless binary steps one directly.
+pub struct World {
+  level: String,
+  areas: Vec<BoxArea>,
+  input: Arc<Mutex<player::Input>>,
+  // refits the ui asked for, applied at the start of the next tick
+  refits: Arc<Mutex<Vec<Refit>>>,
+  ship: MutexEntity,
+  buffs: Vec<BoxBuff>,
+  projectiles: Vec<MutexProjectile>,
+  window_size: Vector2<f32>,
+  ticks: u64,
+  seed: u64,
+  // every random roll in the battle comes from here, scripts included
+  rng: Arc<Mutex<StdRng>>,
+  lockstep: bool,
+  recording: Option<Replay>,
+  next_entity_id: u32,
+  // ship definitions scripts can spawn, loaded once rather than on every spawn
+  ships: Vec<ShipDefinition>,
+  scripting: ScriptApi,
+  script: Option<String>,
+  script_loaded: bool,
+  broadphase_kind: BroadphaseKind,
+  // reused by every collision pass so it isn't reallocated each tick
+  broadphase: Arc<Mutex<BoxBroadphase>>,
+  thread_pool: ThreadPool,
+  tx: mpsc::Sender<()>,
+  rx: mpsc::Receiver<()>,
+}
+
+impl World {
+  pub fn new(areas: Vec<BoxArea>, ship: BoxEntity, window_size: Vector2<f32>) -> World {
+    let (thread_pool, tx, rx) = World::create_collision_pool();
+    let rng = Arc::new(Mutex::new(StdRng::seed_from_u64(DEFAULT_SEED)));
+    
+    let mut input = player::Input::new();
+    input.sync(&ship);
+    
+    let mut world = World {
+      level: String::new(),
+      areas,
+      input: Arc::new(Mutex::new(input)),
+      refits: Arc::new(Mutex::new(Vec::new())),
+      ship: Arc::new(Mutex::new(ship)),
+      buffs: Vec::new(),
+      projectiles: Vec::new(),
+      window_size,
+      ticks: 0,
+      seed: DEFAULT_SEED,
+      rng: Arc::clone(&rng),
+      lockstep: false,
+      recording: None,
+      next_entity_id: 1,
+      ships: ShipDefinition::load_all(SHIP_DIRECTORY),
+      scripting: ScriptApi::new(rng),
+      script: None,
+      script_loaded: false,
+      broadphase_kind: BroadphaseKind::SpatialHash,
+      broadphase: Arc::new(Mutex::new(BroadphaseKind::SpatialHash.create())),
+      thread_pool,
+      tx,
+      rx,
+    };
+    
+    let (entities, _) = world.collision_objects();
+    for mutex_entity in &entities {
+      let mut entity = mutex_entity.lock();
+      entity.set_id(world.next_entity_id);
+      world.next_entity_id += 1;
+    }
+    
+    world
+  }
+  
+  // The player's ship dropped into the named level from resources/levels.
+  pub fn battle(level: &str, mut ship: BoxEntity, window_size: Vector2<f32>) -> Result<World, String> {
+    let level_file = Level::load_named(level)?;
+    let mut areas = level_file.areas()?;
+    
+    let start = Vector2::new(540.0, 600.0);
+    if let Some(escort) = level_file.escort_area(start)? {
+      areas.push(escort);
+    }
+    
+    ship.set_position(start);
+    ship.set_max_shield(100.0);
+    
+    let mut world = World::new(areas, ship, window_size);
+    world.level = level.to_string();
+    
+    if let Some(script) = &level_file.script {
+      let path = Path::new(SCRIPT_DIRECTORY).join(script);
+      let source = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+      world = world.with_script(source);
+    }
+    
+    Ok(world)
+  }
+  
+  pub fn with_seed(mut self, seed: u64) -> World {
+    self.seed = seed;
+    self.rng = Arc::new(Mutex::new(StdRng::seed_from_u64(seed)));
+    self.scripting = ScriptApi::new(Arc::clone(&self.rng));
+    self
+  }
+  
+  // Lua source run once on the first tick that has a Lua state, see scripting.rs for the api.
+  pub fn with_script(mut self, source: String) -> World {
+    self.script = Some(source);
+    self.script_loaded = false;
+    self
+  }
+  
+  // Collisions run on the calling thread every tick instead of on the thread pool whenever
+  // the last pass finished, so the same seed and inputs always give the same world.
+  pub fn deterministic(mut self) -> World {
+    self.lockstep = true;
+    self
+  }
+  
+  // Which broadphase the collision passes use, the spatial hash unless told otherwise
+  pub fn with_broadphase(mut self, kind: BroadphaseKind) -> World {
+    self.broadphase_kind = kind;
+    self.broadphase = Arc::new(Mutex::new(kind.create()));
+    self
+  }
+  
+  // Records the input of every tick from here on, only meaningful on a deterministic world.
+  pub fn with_recording(mut self) -> World {
+    let ship = { self.ship.lock().name() };
+    self.recording = Some(Replay::new(self.seed, ship, self.level.to_string(), self.window_size));
+    self
+  }
+  
+  fn create_collision_pool() -> (ThreadPool, mpsc::Sender<()>, mpsc::Receiver<()>) {
+    let (tx, rx) = mpsc::channel();
+    let thread_pool = ThreadPool::new(5);
+    let fake_tx = tx.clone();
+    thread_pool.execute(move || {
+      let _ = fake_tx.send(());
+    });
+    
+    (thread_pool, tx, rx)
+  }
+  
+  pub fn level(&self) -> String {
+    self.level.to_string()
+  }
+  
+  pub fn areas(&self) -> &Vec<BoxArea> {
+    &self.areas
+  }
+  
+  pub fn input(&self) -> Arc<Mutex<player::Input>> {
+    Arc::clone(&self.input)
+  }
+  
+  pub fn refits(&self) -> Arc<Mutex<Vec<Refit>>> {
+    Arc::clone(&self.refits)
+  }
+  
+  pub fn ship(&self) -> &MutexEntity {
+    &self.ship
+  }
+  
+  pub fn projectiles(&self) -> &Vec<MutexProjectile> {
+    &self.projectiles
+  }
+  
+  pub fn ticks(&self) -> u64 {
+    self.ticks
+  }
+  
+  pub fn seed(&self) -> u64 {
+    self.seed
+  }
+  
+  pub fn broadphase(&self) -> BroadphaseKind {
+    self.broadphase_kind
+  }
+  
+  pub fn is_deterministic(&self) -> bool {
+    self.lockstep
+  }
+  
+  pub fn recording(&self) -> Option<&Replay> {
+    self.recording.as_ref()
+  }
+  
+  pub fn rng(&self) -> Arc<Mutex<StdRng>> {
+    Arc::clone(&self.rng)
+  }
+  
+  // Position, velocity and health of every area entity followed by the ship, in update order.
+  pub fn entity_states(&self) -> Vec<(Vector2<f32>, Vector2<f32>, f32)> {
+    let mut states = Vec::new();
+    
+    let (entities, _) = self.collision_objects();
+    for mutex_entity in &entities {
+      let entity = mutex_entity.lock();
+      states.push((entity.position(), entity.velocity(), entity.health()));
+    }
+    
+    states
+  }
+  
+  pub fn entity_by_id(&self, id: u32) -> Option<MutexEntity> {
+    let (entities, _) = self.collision_objects();
+    entities.into_iter().find(|mutex_entity| mutex_entity.lock().id() == id)
+  }
+  
+  pub fn ship_destroyed(&self) -> bool {
+    let ship = self.ship.lock();
+    !ship.should_exist()
+  }
+  
+  pub fn set_window_size(&mut self, window_size: Vector2<f32>) {
+    self.window_size = window_size;
+  }
+  
+  // Steps the world with input read back from a replay, including the ability bindings,
+  // refits and window size the player had on that tick.
+  pub fn replay_tick(&mut self, tick: &TickInput, lua: Option<&mut Lua>, delta_time: f32) {
+    {
+      let ship = self.ship.lock();
+      self.input.lock().set_bindings(tick.bindings, &ship);
+    }
+    *self.refits.lock() = tick.refits.clone();
+    self.window_size = tick.window_size;
+    self.update(&tick.state, lua, delta_time);
+  }
+  
+  pub fn update(&mut self, input_state: &InputState, mut lua: Option<&mut Lua>, delta_time: f32) {
+    let refits: Vec<Refit> = self.refits.lock().drain(..).collect();
+    if let Some(recording) = &mut self.recording {
+      let bindings = { self.input.lock().bindings() };
+      recording.record(TickInput {
+        state: *input_state,
+        bindings,
+        refits: refits.clone(),
+        window_size: self.window_size,
+      });
+    }
+    
+    {
+      let mut ship = self.ship.lock();
+      for refit in &refits {
+        refit.apply(&mut ship);
+      }
+    }
+    
+    if let Some(lua) = &mut lua {
+      self.load_script(lua);
+      self.publish_script_state();
+    }
+    
+    self.apply_gravity();
+    let player_projectiles = self.update_player(input_state, delta_time);
+    let targets = self.target_view();
+    let entity_projectiles = self.update_areas(&targets, lua, delta_time);
+    self.apply_script_commands();
+    self.update_projectiles(player_projectiles, entity_projectiles, delta_time);
+    self.update_collisions();
+    
+    self.ticks += 1;
+  }
+  
+  // Wells carried by entities in areas with gravity on
+  pub fn gravity_field(&self) -> GravityField {
+    let mut field = GravityField::new();
+    for area in self.areas.iter().filter(|area| area.has_gravity()) {
+      for mutex_entity in &area.entities() {
+        let entity = mutex_entity.lock();
+        if let Some(well) = entity.gravity_well() {
+          if entity.should_exist() {
+            field.add(entity.id(), entity.position(), well);
+          }
+        }
+      }
+    }
+    
+    field
+  }
+  
+  // Queues this tick's pull on every entity and projectile, applied in their physics
+  fn apply_gravity(&mut self) {
+    let field = self.gravity_field();
+    if field.is_empty() {
+      return;
+    }
+    
+    let (entities, projectiles) = self.collision_objects();
+    for mutex_entity in &entities {
+      let mut entity = mutex_entity.lock();
+      let acceleration = field.acceleration_at(entity.position(), entity.id());
+      entity.pull(acceleration);
+    }
+    
+    for mutex_projectile in &projectiles {
+      let mut projectile = mutex_projectile.lock();
+      let acceleration = field.acceleration_at(projectile.position(), 0);
+      projectile.pull(acceleration);
+    }
+  }
+  
+  fn update_player(&mut self, input_state: &InputState, delta_time: f32) -> Vec<BoxProjectile> {
+    let mut ship = self.ship.lock();
+    let mut player_input = self.input.lock();
+    player_input.update(&mut *ship, input_state, self.window_size, delta_time);
+    
+    let (new_buffs, new_projectiles) = ship.update(delta_time);
+    
+    buffs::update_buffs(&mut self.buffs, &mut *ship, delta_time);
+    for buff in new_buffs {
+      buffs::add_buff(&mut self.buffs, buff, &mut *ship);
+    }
+    
+    new_projectiles
+  }
+  
+  fn load_script(&mut self, lua: &mut Lua) {
+    if self.script_loaded {
+      return;
+    }
+    self.script_loaded = true;
+    
+    self.scripting.register(lua);
+    if let Some(source) = &self.script {
+      if let Err(e) = lua.execute::<()>(source) {
+        println!("Failed to run level script: {:?}", e);
+      }
+    }
+  }
+  
+  // Every area entity followed by the ship, and the ship's id
+  fn snapshots(&self) -> (Vec<EntitySnapshot>, u32) {
+    let mut entities = Vec::new();
+    for i in 0..self.areas.len() {
+      for mutex_entity in &self.areas[i].entities() {
+        let entity = mutex_entity.lock();
+        entities.push(World::snapshot(&*entity, Some(i)));
+      }
+    }
+    let player_id = {
+      let ship = self.ship.lock();
+      entities.push(World::snapshot(&*ship, None));
+      ship.id()
+    };
+    
+    (entities, player_id)
+  }
+  
+  fn target_view(&self) -> TargetView {
+    let (entities, player_id) = self.snapshots();
+    TargetView::new(entities, player_id)
+  }
+  
+  fn publish_script_state(&mut self) {
+    let (entities, player_id) = self.snapshots();
+    
+    let mut state = self.scripting.state().lock();
+    state.tick = self.ticks;
+    state.player_id = player_id;
+    state.next_id = self.next_entity_id;
+    state.num_areas = self.areas.len();
+    state.entities = entities;
+  }
+  
+  fn snapshot(entity: &BoxEntity, area: Option<usize>) -> EntitySnapshot {
+    EntitySnapshot {
+      id: entity.id(),
+      area,
+      position: entity.position(),
+      velocity: entity.velocity(),
+      rotation: entity.rotation(),
+      health: entity.health(),
+      max_health: entity.max_health(),
+      hostility: entity.hostility().clone(),
+      last_attacker: entity.last_attacker(),
+    }
+  }
+  
+  fn apply_script_commands(&mut self) {
+    let commands = self.scripting.take_commands();
+    if commands.is_empty() {
+      return;
+    }
+    
+    self.next_entity_id = self.next_entity_id.max(self.scripting.state().lock().next_id);
+    
+    for command in commands {
+      match command {
+        ScriptCommand::Spawn { id, area, kind, position, hostility, controller, abilities } => {
+          if area >= self.areas.len() {
+            println!("Script tried to spawn {} in area {} which doesn't exist", kind, area);
+            continue;
+          }
+          
+          let (hostility, controller) = match (scripting::parse_hostility(&hostility), ControllerKind::from_name(&controller)) {
+            (Some(hostility), Some(controller)) => (hostility, controller),
+            _ => {
+              println!("Script tried to spawn {} with unknown hostility {} or controller {}", kind, hostility, controller);
+              continue;
+            }
+          };
+          
+          let definition = EntityDefinition {
+            kind: EntityKind::from_name(&kind),
+            position: (position.x, position.y),
+            size: None,
+            hostility,
+            controller,
+            abilities: abilities.into_iter().map(|name| AbilityDefinition { name, passives: Vec::new() }).collect(),
+            grid: None,
+          };
+          
+          match definition.to_full_entities(self.areas[area].position(), &self.ships) {
+            Ok(full_entities) => {
+              // the script was handed id for the first, any others get ids of their own
+              for (i, full_entity) in full_entities.into_iter().enumerate() {
+                if i == 0 {
+                  full_entity.entity.lock().set_id(id);
+                } else {
+                  full_entity.entity.lock().set_id(self.next_entity_id);
+                  self.next_entity_id += 1;
+                }
+                self.areas[area].add_entity(full_entity);
+              }
+            },
+            Err(e) => {
+              println!("Script failed to spawn {}: {}", kind, e);
+            }
+          }
+        },
+        ScriptCommand::ApplyBuff { id, buff } => {
+          match (self.entity_by_id(id), buffs::buff_by_name(&buff)) {
+            (Some(mutex_entity), Some(buff)) => {
+              mutex_entity.lock().activate_buff(buff);
+            },
+            _ => {
+              println!("Script tried to apply unknown buff {} or to missing entity {}", buff, id);
+            }
+          }
+        },
+        ScriptCommand::FireAbility { id, ability, target } => {
+          match (self.entity_by_id(id), abilities::ability_by_name(&ability)) {
+            (Some(mutex_entity), Some(mut ability)) => {
+              let mut entity = mutex_entity.lock();
+              let hostility = entity.hostility().clone();
+              ability.activate(&mut *entity, target, self.window_size, &hostility);
+            },
+            _ => {
+              println!("Script tried to fire unknown ability {} from missing entity {}", ability, id);
+            }
+          }
+        },
+        ScriptCommand::AreaCallback { area, function } => {
+          if area < self.areas.len() {
+            self.areas[area].add_callback(function);
+          } else {
+            println!("Script registered {} on area {} which doesn't exist", function, area);
+          }
+        },
+      }
+    }
+  }
+  
+  fn update_areas(&mut self, targets: &TargetView, mut lua: Option<&mut Lua>, delta_time: f32) -> Vec<BoxProjectile> {
+    let mut new_projectiles = Vec::new();
+    
+    for area in &mut self.areas {
+      area.attach_scripting(&self.scripting);
+      let projectiles = area.update(targets, self.window_size, lua.as_mut().map(|lua| &mut **lua), delta_time);
+      for projectile in projectiles {
+        new_projectiles.push(projectile);
+      }
+    }
+    
+    new_projectiles
+  }
+  
+  fn update_projectiles(&mut self, player_projectiles: Vec<BoxProjectile>, entity_projectiles: Vec<BoxProjectile>, delta_time: f32) {
+    for new_projectile in player_projectiles {
+      self.projectiles.push(Arc::new(Mutex::new(new_projectile)));
+    }
+    
+    for new_projectile in entity_projectiles {
+      self.projectiles.push(Arc::new(Mutex::new(new_projectile)));
+    }
+    
+    let mut offset = 0;
+    for i in 0..self.projectiles.len() {
+      if i < offset {
+        break;
+      }
+      
+      let projectile_should_exist;
+      {
+        let mut projectile = self.projectiles[i-offset].lock();
+        projectile.update(delta_time);
+        projectile_should_exist = projectile.should_exist();
+      }
+      if !projectile_should_exist {
+        self.projectiles.remove(i-offset);
+        offset += 1;
+      }
+    }
+  }
+  
+  fn collision_objects(&self) -> (Vec<MutexEntity>, Vec<MutexProjectile>) {
+    let mut entities: Vec<MutexEntity> = Vec::new();
+    let mut projectiles: Vec<MutexProjectile> = Vec::new();
+    for area in &self.areas {
+      for mutex_entity in &area.entities() {
+        entities.push(Arc::clone(&mutex_entity));
+      }
+    }
+    entities.push(Arc::clone(&self.ship));
+    
+    for i in 0..self.projectiles.len() {
+      projectiles.push(Arc::clone(&self.projectiles[i]));
+    }
+    
+    (entities, projectiles)
+  }
+  
+  fn update_collisions(&mut self) {
+    if self.lockstep {
+      let (entities, projectiles) = self.collision_objects();
+      collisions::lockstep_collisions(&entities, &projectiles, &mut self.broadphase.lock());
+    } else if self.rx.try_recv().is_ok() {
+      let (entities, projectiles) = self.collision_objects();
+      
+      let broadphase = Arc::clone(&self.broadphase);
+      let tx = self.tx.clone();
+      self.thread_pool.execute(move || {
+        collisions::collisions(entities, projectiles, &mut broadphase.lock());
+        let _ = tx.send(());
+      });
+    }
+  }
+}
+
+impl Clone for World {
+  fn clone(&self) -> World {
+    let (thread_pool, tx, rx) = World::create_collision_pool();
+    
+    World {
+      level: self.level.to_string(),
+      areas: self.areas.clone(),
+      input: Arc::clone(&self.input),
+      refits: Arc::clone(&self.refits),
+      ship: Arc::clone(&self.ship),
+      buffs: self.buffs.clone(),
+      projectiles: self.projectiles.clone(),
+      window_size: self.window_size,
+      ticks: self.ticks,
+      seed: self.seed,
+      rng: Arc::clone(&self.rng),
+      lockstep: self.lockstep,
+      recording: self.recording.clone(),
+      next_entity_id: self.next_entity_id,
+      ships: self.ships.clone(),
+      scripting: self.scripting.clone(),
+      script: self.script.clone(),
+      script_loaded: self.script_loaded,
+      broadphase_kind: self.broadphase_kind,
+      broadphase: Arc::new(Mutex::new(self.broadphase_kind.create())),
+      thread_pool,
+      tx,
+      rx,
+    }
+  }
+}
@@ -0,0 +1,106 @@
+extern crate acwars;
+extern crate rand;
+extern crate parking_lot;
+
+use acwars::modules::entities::{Ship, Brew, Wall, Astroid, BoxEntity, MutexEntity};
+use acwars::modules::projectiles::{BoxProjectile, Gob, Ftpl, LaserBeam, Aoe};
+use acwars::modules::spatial_hash::SpatialObject;
+use acwars::modules::colliders::colliders_intersect;
+use acwars::modules::broadphase::{BroadphaseKind, BoxBroadphase, SpatialHashBroadphase};
+
+use acwars::cgmath::Vector2;
+
+use parking_lot::Mutex;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use std::sync::Arc;
+
+const SEED: u64 = 645;
+const TRIALS: usize = 200;
+
+fn random_entity(rng: &mut StdRng, origin: Vector2<f32>, spread: f32) -> MutexEntity {
+  let position = origin + Vector2::new(rng.gen_range(-spread, spread), rng.gen_range(-spread, spread));
+  let mut entity: BoxEntity = match rng.gen_range(0, 4) {
+    0 => Box::new(Brew::new(position)),
+    1 => Box::new(Wall::new(position)),
+    2 => Box::new(Ship::new(position)),
+    _ => Box::new(Astroid::new(position, Vector2::new(rng.gen_range(5.0, 400.0), rng.gen_range(5.0, 400.0)))),
+  };
+  entity.set_rotation(rng.gen_range(0.0, 360.0));
+  
+  Arc::new(Mutex::new(entity))
+}
+
+fn random_projectile(rng: &mut StdRng, origin: Vector2<f32>, spread: f32) -> BoxProjectile {
+  let position = origin + Vector2::new(rng.gen_range(-spread, spread), rng.gen_range(-spread, spread));
+  let angle: f32 = rng.gen_range(0.0, 360.0);
+  let direction = Vector2::new(angle.to_radians().cos(), angle.to_radians().sin());
+  let offset = Vector2::new(rng.gen_range(0.0, 50.0), rng.gen_range(0.0, 50.0));
+  
+  match rng.gen_range(0, 4) {
+    0 => Box::new(Gob::new(position, offset, direction)),
+    1 => Box::new(Ftpl::new(position, offset, direction)),
+    2 => Box::new(LaserBeam::new(position, offset, direction)),
+    _ => Box::new(Aoe::new(position, rng.gen_range(10.0, 200.0))),
+  }
+}
+
+// Every backend, with the spatial hash at several cell sizes
+fn backends() -> Vec<(String, BoxBroadphase)> {
+  let mut backends: Vec<(String, BoxBroadphase)> = Vec::new();
+  for &cell_size in &[7.5, 30.0, 100.0, 512.0, 5000.0] {
+    backends.push((format!("spatial-hash {}", cell_size), Box::new(SpatialHashBroadphase::new(cell_size))));
+  }
+  backends.push(("brute-force".to_string(), BroadphaseKind::BruteForce.create()));
+  backends.push(("kd-tree".to_string(), BroadphaseKind::KdTree.create()));
+  
+  backends
+}
+
+// Scatters entities and projectiles around the origin, often far into negative coordinates,
+// and checks every broadphase offers up every pair a brute force check finds touching.
+#[test]
+fn backends_find_every_touching_pair() {
+  let mut rng = StdRng::seed_from_u64(SEED);
+  let mut backends = backends();
+  
+  for _ in 0..TRIALS {
+    let origin = Vector2::new(rng.gen_range(-50000.0, 50000.0), rng.gen_range(-50000.0, 50000.0));
+    let spread = rng.gen_range(10.0, 2000.0);
+    
+    let entities: Vec<MutexEntity> = (0..40).map(|_| random_entity(&mut rng, origin, spread)).collect();
+    let projectiles: Vec<BoxProjectile> = (0..40).map(|_| random_projectile(&mut rng, origin, spread)).collect();
+    
+    for (name, backend) in &mut backends {
+      backend.build(&entities);
+      let entity_pairs = backend.pairs();
+      
+      for projectile in &projectiles {
+        let (min, max) = projectile.bounds();
+        let candidates = backend.query(min, max);
+        for entity in &entities {
+          if colliders_intersect(&projectile.colliders(), &entity.lock().colliders()) {
+            assert!(candidates.iter().any(|candidate| Arc::ptr_eq(candidate, entity)),
+                    "{} missed a projectile at {:?}", name, projectile.position());
+          }
+        }
+      }
+      
+      for i in 0..entities.len() {
+        for j in i+1..entities.len() {
+          if colliders_intersect(&entities[i].lock().colliders(), &entities[j].lock().colliders()) {
+            let found = entity_pairs.iter().any(|(a, b)| {
+              (Arc::ptr_eq(a, &entities[i]) && Arc::ptr_eq(b, &entities[j])) ||
+              (Arc::ptr_eq(a, &entities[j]) && Arc::ptr_eq(b, &entities[i]))
+            });
+            assert!(found, "{} missed entities {} and {}", name, i, j);
+          }
+        }
+      }
+      
+      backend.clear();
+    }
+  }
+}
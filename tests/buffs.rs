@@ -0,0 +1,168 @@
+extern crate acwars;
+
+use acwars::modules::entities::{Ship, BoxEntity, Hostility};
+use acwars::modules::abilities::{BoxAbility, SingleShot, Move, Shatter};
+use acwars::modules::projectiles::{BoxProjectile, Gob};
+use acwars::modules::damage::{Damage, DamageType};
+use acwars::modules::buffs::{self, BoxBuff, StatusKind, MaxSpeedBuff, SpeedBuff, PhaseBuff, Burn, Slow, Stun, Disarm, Regen};
+use acwars::modules::stats::Modifier;
+use acwars::DELTA_STEP;
+
+use acwars::cgmath::{Vector2, InnerSpace};
+
+fn window_size() -> Vector2<f32> {
+  Vector2::new(1280.0, 1080.0)
+}
+
+fn hostile_ship() -> BoxEntity {
+  Box::new(Ship::new(Vector2::new(0.0, 0.0)).as_hostile())
+}
+
+// Every ordering of 0..n
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+  if n == 0 {
+    return vec!(Vec::new());
+  }
+  
+  let mut orders = Vec::new();
+  for order in permutations(n-1) {
+    for i in 0..n {
+      let mut order = order.clone();
+      order.insert(i, n-1);
+      orders.push(order);
+    }
+  }
+  
+  orders
+}
+
+#[test]
+fn burns_stack_and_tick() {
+  let ship = hostile_ship();
+  let mut burning = ship.clone();
+  let mut effects: Vec<BoxBuff> = Vec::new();
+  for _ in 0..3 {
+    buffs::add_buff(&mut effects, Box::new(Burn::new().with_damage(1.0)), &mut burning);
+  }
+  buffs::update_buffs(&mut effects, &mut burning, 0.5);
+  
+  let expected = ship.hull_resistances().reduce(&Damage::new(3.0, DamageType::Thermal));
+  assert_eq!(burning.statuses(), vec!((StatusKind::Burn, 3)));
+  assert!((ship.health() - burning.health() - expected).abs() < 0.001);
+}
+
+#[test]
+fn haste_refreshes_and_runs_out() {
+  let ship = hostile_ship();
+  let mut hasted = ship.clone();
+  let mut effects: Vec<BoxBuff> = Vec::new();
+  buffs::add_buff(&mut effects, Box::new(MaxSpeedBuff::new()), &mut hasted);
+  buffs::add_buff(&mut effects, Box::new(MaxSpeedBuff::new()), &mut hasted);
+  assert_eq!(effects.len(), 1);
+  assert!((hasted.max_velocity() - ship.max_velocity()*1.5).abs() < 0.001);
+  
+  buffs::update_buffs(&mut effects, &mut hasted, 10.0);
+  assert!((hasted.max_velocity() - ship.max_velocity()).abs() < 0.001);
+  assert!(hasted.statuses().is_empty());
+}
+
+// A stunned ship can't thrust or fire and a second stun doesn't extend it, a disarmed one can
+// move but not fire
+#[test]
+fn stun_and_disarm() {
+  let target = Vector2::new(0.0, 1000.0);
+  let mut stunned = hostile_ship();
+  let mut effects: Vec<BoxBuff> = Vec::new();
+  buffs::add_buff(&mut effects, Box::new(Stun::new().with_timer(1.0)), &mut stunned);
+  buffs::update_buffs(&mut effects, &mut stunned, 0.5);
+  buffs::add_buff(&mut effects, Box::new(Stun::new().with_timer(1.0)), &mut stunned);
+  
+  let mut shot: BoxAbility = Box::new(SingleShot::new());
+  shot.activate(&mut stunned, target, window_size(), &Hostility::Hostile);
+  stunned.add_acceleration(Vector2::new(0.0, 1.0));
+  stunned.physics(0.1);
+  assert!(stunned.return_projectiles().is_empty());
+  assert_eq!(stunned.velocity().magnitude(), 0.0);
+  assert!((effects[0].timer() - 0.5).abs() < 0.001);
+  
+  buffs::update_buffs(&mut effects, &mut stunned, 1.0);
+  buffs::add_buff(&mut effects, Box::new(Disarm::new()), &mut stunned);
+  shot.activate(&mut stunned, target, window_size(), &Hostility::Hostile);
+  let mut movement: BoxAbility = Box::new(Move::new());
+  movement.activate(&mut stunned, target, window_size(), &Hostility::Hostile);
+  stunned.physics(0.1);
+  assert!(!stunned.is_stunned());
+  assert!(stunned.return_projectiles().is_empty());
+  assert!(stunned.velocity().magnitude() > 0.0);
+}
+
+#[test]
+fn regen_heals() {
+  let mut healing = hostile_ship();
+  healing.hit(Damage::new(10.0, DamageType::Energy));
+  let health = healing.health();
+  let mut effects: Vec<BoxBuff> = Vec::new();
+  buffs::add_buff(&mut effects, Box::new(Regen::new().with_health(2.0)), &mut healing);
+  buffs::update_buffs(&mut effects, &mut healing, 1.0);
+  assert!((healing.health() - health - 2.0).abs() < 0.001);
+}
+
+#[test]
+fn projectile_passive_carries_effect() {
+  let ship = hostile_ship();
+  let mut projectile: BoxProjectile = Box::new(Gob::new(Vector2::new(0.0, -1000.0), Vector2::new(0.0, 0.0), Vector2::new(0.0, 1.0)));
+  projectile.add_passive(Box::new(Shatter::new().with_status_effect(Box::new(Slow::new()))));
+  let factor = 2000.0/DELTA_STEP/projectile.velocity().magnitude();
+  projectile.multiply_velocity(factor);
+  projectile.physics(DELTA_STEP);
+  
+  let mut slowed = ship.clone();
+  projectile.collide_with(&mut slowed);
+  let mut effects: Vec<BoxBuff> = Vec::new();
+  for buff in slowed.return_buffs() {
+    buffs::add_buff(&mut effects, buff, &mut slowed);
+  }
+  assert_eq!(slowed.statuses(), vec!((StatusKind::Slow, 1)));
+  assert!(slowed.max_velocity() < ship.max_velocity());
+}
+
+// Buffs applied and expiring in every order, including a Dash landing on a Haste, keep max
+// velocity at the base times the active buffs' factors and leave every stat back at its base
+#[test]
+fn buffs_in_any_order_return_to_base() {
+  let ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  let base = ship.base_stats();
+  
+  let effects = |i: usize| -> BoxBuff {
+    match i {
+      0 => Box::new(MaxSpeedBuff::new().with_timer(1.0)),
+      1 => Box::new(MaxSpeedBuff::new().with_timer(0.25).with_multiplier(5.0)),
+      2 => Box::new(Slow::new().with_timer(2.0)),
+      3 => Box::new(SpeedBuff::new().with_timer(0.25)),
+      _ => Box::new(PhaseBuff::new().with_timer(1.5)),
+    }
+  };
+  
+  for order in &permutations(5) {
+    let mut buffed = ship.clone();
+    let mut active: Vec<BoxBuff> = Vec::new();
+    for step in 0..order.len()+40 {
+      if let Some(&i) = order.get(step) {
+        buffs::add_buff(&mut active, effects(i), &mut buffed);
+      }
+      buffs::update_buffs(&mut active, &mut buffed, 0.1);
+      
+      let factor: f32 = active.iter().flat_map(|buff| buff.modifiers()).map(|modifier| {
+        match modifier.modifier() {
+          Modifier::Multiply(factor) => factor,
+          _ => 1.0,
+        }
+      }).product();
+      assert!((buffed.max_velocity() - base.max_velocity*factor).abs() < 0.001, "order {:?} drifted at step {}", order, step);
+    }
+    
+    assert!(active.is_empty());
+    assert_eq!(buffed.stats(), base, "order {:?}", order);
+    assert!(!buffed.is_in_phase_mode());
+  }
+}
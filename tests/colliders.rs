@@ -0,0 +1,118 @@
+extern crate acwars;
+extern crate rand;
+
+use acwars::DELTA_STEP;
+use acwars::modules::entities::{Ship, Wall, Astroid, BoxEntity};
+use acwars::modules::projectiles::{BoxProjectile, Ftpl};
+use acwars::modules::colliders::{self, Collider, colliders_intersect};
+
+use acwars::cgmath::{Vector2, InnerSpace};
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+const SEED: u64 = 645;
+const TRIALS: usize = 2000;
+
+fn random_point(rng: &mut StdRng, min: Vector2<f32>, max: Vector2<f32>) -> Vector2<f32> {
+  Vector2::new(rng.gen_range(min.x, max.x.max(min.x+0.001)), rng.gen_range(min.y, max.y.max(min.y+0.001)))
+}
+
+fn random_collider(rng: &mut StdRng) -> Collider {
+  let center = random_point(rng, Vector2::new(-100.0, -100.0), Vector2::new(100.0, 100.0));
+  let reach = Vector2::new(100.0, 100.0);
+  match rng.gen_range(0, 4) {
+    0 => Collider::circle(center, rng.gen_range(1.0, 100.0)),
+    1 => Collider::oriented_box(center, Vector2::new(rng.gen_range(1.0, 200.0), rng.gen_range(1.0, 200.0)), rng.gen_range(0.0, 360.0)),
+    2 => Collider::capsule(center, random_point(rng, center-reach, center+reach), rng.gen_range(1.0, 50.0)),
+    _ => Collider::polygon((0..rng.gen_range(3, 9)).map(|_| random_point(rng, center-reach, center+reach)).collect()),
+  }
+}
+
+// The narrowphase on random pairs of every shape has to agree both ways round, stay inside
+// the bounds and never miss a point the two share.
+#[test]
+fn random_pairs_agree() {
+  let mut rng = StdRng::seed_from_u64(SEED);
+  
+  for _ in 0..TRIALS {
+    let a = random_collider(&mut rng);
+    let b = random_collider(&mut rng);
+    let intersects = a.intersects(&b);
+    assert_eq!(intersects, b.intersects(&a), "{:?} and {:?} disagree on intersecting", a, b);
+    
+    let (a_min, a_max) = a.bounds();
+    let (b_min, b_max) = b.bounds();
+    if intersects {
+      assert!(a_max.x >= b_min.x && b_max.x >= a_min.x && a_max.y >= b_min.y && b_max.y >= a_min.y,
+              "{:?} and {:?} intersect with separate bounds", a, b);
+    }
+    
+    for _ in 0..50 {
+      let point = random_point(&mut rng, a_min, a_max);
+      assert!(intersects || !(a.contains_point(point) && b.contains_point(point)),
+              "{:?} and {:?} share {:?} but don't intersect", a, b, point);
+    }
+  }
+}
+
+// A gap between the circles walls used to be made of, and just past their last circle
+#[test]
+fn wall_hits_what_it_covers() {
+  let wall: BoxEntity = Box::new(Wall::new(Vector2::new(0.0, 0.0)));
+  
+  assert!(colliders_intersect(&wall.colliders(), &vec!(Collider::circle(Vector2::new(31.25, 22.0), 5.0))));
+  assert!(!colliders_intersect(&wall.colliders(), &vec!(Collider::circle(Vector2::new(395.0, 0.0), 5.0))));
+}
+
+// A ship dropping onto the top of a wall from 100 above, 200 of it in one step
+#[test]
+fn swept_box_time_of_impact() {
+  let wall: BoxEntity = Box::new(Wall::new(Vector2::new(0.0, 0.0)));
+  let mut ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  let radius = ship.collision_circles()[0].z;
+  ship.set_position(Vector2::new(0.0, 25.0 + radius + 100.0));
+  ship.set_velocity(Vector2::new(0.0, -200.0/DELTA_STEP));
+  ship.physics(DELTA_STEP);
+  
+  let time = colliders::time_of_impact(&ship.colliders(), ship.motion(), &wall.colliders(), wall.motion());
+  assert!(time.map(|time| (time - 0.5).abs() < 0.001).unwrap_or(false), "ship hit the wall at {:?}, expected 0.5", time);
+}
+
+// Fires shots and flies a ship straight through a small Astroid in a single step, far enough
+// that neither overlaps it before or after, and checks the sweep still finds the impact.
+#[test]
+fn no_tunnelling() {
+  for &distance in &[100.0, 1000.0, 10000.0, 100000.0] {
+    let start = Vector2::new(0.0, -distance*0.5);
+    let speed = distance/DELTA_STEP;
+    
+    let astroid: BoxEntity = Box::new(Astroid::new(Vector2::new(0.0, 0.0), Vector2::new(20.0, 20.0)));
+    let astroid_radius = astroid.collision_circles()[0].z;
+    
+    let mut projectile: BoxProjectile = Box::new(Ftpl::new(start, Vector2::new(0.0, 0.0), Vector2::new(0.0, 1.0)));
+    let factor = speed/projectile.velocity().magnitude();
+    projectile.multiply_velocity(factor);
+    projectile.physics(DELTA_STEP);
+    let expected = (distance*0.5 - astroid_radius - projectile.collision_circles()[0].z)/distance;
+    
+    assert!(!colliders_intersect(&projectile.colliders(), &astroid.colliders()));
+    let mut target = astroid.clone();
+    let time = projectile.collide_with(&mut target);
+    assert!(time.map(|time| (time - expected).abs() < 0.001).unwrap_or(false),
+            "projectile over {} hit at {:?}, expected {}", distance, time, expected);
+    assert!(!projectile.should_exist());
+    
+    let mut ship: BoxEntity = Box::new(Ship::new(start));
+    ship.set_velocity(Vector2::new(0.0, speed));
+    ship.physics(DELTA_STEP);
+    let expected = (distance*0.5 - astroid_radius - ship.collision_circles()[0].z)/distance;
+    let contact = start.y + distance*expected;
+    
+    let mut target = astroid.clone();
+    let time = ship.collide_with(&mut target);
+    assert!(time.map(|time| (time - expected).abs() < 0.001).unwrap_or(false),
+            "ship over {} hit at {:?}, expected {}", distance, time, expected);
+    assert!((ship.position().y - contact).abs() < 0.1, "ship over {} stopped at {}, expected {}", distance, ship.position().y, contact);
+  }
+}
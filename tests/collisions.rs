@@ -0,0 +1,57 @@
+extern crate acwars;
+
+use acwars::modules::entities::{Ship, Sun, Astroid, BoxEntity};
+
+use acwars::cgmath::{Vector2, InnerSpace};
+
+const SPEED: f32 = 400.0;
+
+// Ship driven into target from just touching it, returning the speed they part at
+fn bounce(target: &mut BoxEntity, ship: &mut BoxEntity) -> f32 {
+  let ship_radius = ship.colliders()[0].bounds().1.x;
+  let target_radius = target.colliders()[0].bounds().1.x;
+  target.set_position(Vector2::new(0.0, ship_radius + target_radius - 0.25));
+  ship.set_velocity(Vector2::new(0.0, SPEED));
+  
+  ship.collide_with(target);
+  target.velocity().y - ship.velocity().y
+}
+
+#[test]
+fn bounce_keeps_momentum() {
+  let mut ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  let mut astroid: BoxEntity = Box::new(Astroid::new(Vector2::new(0.0, 0.0), Vector2::new(100.0, 100.0)));
+  let momentum = Vector2::new(0.0, SPEED)*ship.mass();
+  
+  let parting = bounce(&mut astroid, &mut ship);
+  let after = ship.velocity()*ship.mass() + astroid.velocity()*astroid.mass();
+  assert!((parting - SPEED*0.5).abs() < 0.5, "parted at {}, expected {}", parting, SPEED*0.5);
+  assert!((after - momentum).magnitude() < momentum.magnitude()*0.001, "momentum went from {:?} to {:?}", momentum, after);
+}
+
+#[test]
+fn sun_is_immovable() {
+  let mut ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  let mut sun: BoxEntity = Box::new(Sun::new(Vector2::new(0.0, 0.0)));
+  
+  let parting = bounce(&mut sun, &mut ship);
+  assert!((parting - SPEED*0.5).abs() < 0.5, "parted at {}, expected {}", parting, SPEED*0.5);
+  assert_eq!(sun.velocity().magnitude(), 0.0);
+}
+
+// Overlapping Astroids are pushed apart without being sent flying or hurt
+#[test]
+fn overlap_is_separated() {
+  let mut astroid: BoxEntity = Box::new(Astroid::new(Vector2::new(0.0, 0.0), Vector2::new(100.0, 100.0)));
+  let mut other: BoxEntity = Box::new(Astroid::new(Vector2::new(30.0, 0.0), Vector2::new(100.0, 100.0)));
+  let health = astroid.health();
+  for _ in 0..20 {
+    astroid.collide_with(&mut other);
+  }
+  
+  let gap = (other.position() - astroid.position()).magnitude();
+  let touching = astroid.colliders()[0].bounds().1.x*2.0;
+  assert!((gap - touching).abs() <= 1.0, "ended {} apart, expected {}", gap, touching);
+  assert_eq!(astroid.velocity().magnitude(), 0.0);
+  assert_eq!(astroid.health(), health);
+}
@@ -0,0 +1,174 @@
+extern crate acwars;
+
+use acwars::modules::entities::{Ship, Sun, Astroid, Wall, BoxEntity, ShieldArc, ShipDefinition};
+use acwars::modules::projectiles::{BoxProjectile, Gob, Ftpl, LaserBeam, Aoe};
+use acwars::modules::damage::{Damage, DamageType, Resistances};
+
+use acwars::cgmath::Vector2;
+
+const SHIELD_DELAY: f32 = 2.0;
+const HULL_MATERIAL: usize = 7;
+
+fn shielded_ship() -> BoxEntity {
+  let mut ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  ship.set_max_shield(100.0);
+  ship.set_shield_regen(50.0);
+  ship.set_shield_delay(SHIELD_DELAY);
+  ship.gain_shield(100.0);
+  ship
+}
+
+#[test]
+fn projectiles_declare_damage_type() {
+  let position = Vector2::new(0.0, 0.0);
+  let direction = Vector2::new(0.0, 1.0);
+  
+  let projectiles: Vec<(BoxProjectile, DamageType)> = vec!(
+    (Box::new(Gob::new(position, position, direction)), DamageType::Kinetic),
+    (Box::new(Ftpl::new(position, position, direction)), DamageType::Kinetic),
+    (Box::new(LaserBeam::new(position, position, direction)), DamageType::Energy),
+    (Box::new(Aoe::new(position, 100.0)), DamageType::Thermal),
+  );
+  for (projectile, expected) in &projectiles {
+    assert_eq!(projectile.damage_type(), *expected);
+  }
+}
+
+// Shields shrug off energy and buckle under kinetic hits, and neither reaches the hull
+#[test]
+fn shields_resist_energy() {
+  let ship = shielded_ship();
+  
+  let mut energy_hit = ship.clone();
+  energy_hit.hit(Damage::new(20.0, DamageType::Energy));
+  let mut kinetic_hit = ship.clone();
+  kinetic_hit.hit(Damage::new(20.0, DamageType::Kinetic));
+  
+  assert!(100.0 - energy_hit.shield() < 100.0 - kinetic_hit.shield());
+  assert_eq!(energy_hit.health(), ship.health());
+  assert_eq!(kinetic_hit.health(), ship.health());
+}
+
+// What breaks through the shield is reduced by the hull's resistances
+#[test]
+fn breaking_the_shield_lets_the_rest_through() {
+  let ship = shielded_ship();
+  let mut broken = ship.clone();
+  let kinetic = Damage::new(200.0, DamageType::Kinetic);
+  let taken = broken.hit(kinetic);
+  
+  let against_shield = broken.shield_resistances().reduce(&kinetic);
+  let expected = broken.hull_resistances().reduce(&kinetic.with_amount(200.0*(1.0 - 100.0/against_shield)));
+  assert_eq!(broken.shield(), 0.0);
+  assert!((taken - expected).abs() < 0.001, "let {} through, expected {}", taken, expected);
+  assert!((ship.health() - broken.health() - taken).abs() < 0.001);
+}
+
+#[test]
+fn hull_resists_by_type() {
+  let ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  for &damage_type in &[DamageType::Kinetic, DamageType::Thermal, DamageType::Energy, DamageType::Collision] {
+    let mut hit = ship.clone();
+    let taken = hit.hit(Damage::new(10.0, damage_type));
+    let expected = 10.0*(1.0 - ship.hull_resistances().resistance(damage_type));
+    assert!((taken - expected).abs() < 0.001, "10 {:?} took {}, expected {}", damage_type, taken, expected);
+  }
+}
+
+// The hull only resists while its hull material section stands
+#[test]
+fn resistances_come_from_hull_material() {
+  let mut ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  assert_eq!(ship.hull_resistances().resistance(DamageType::Kinetic), 0.2);
+  
+  let (min, max) = ship.section_colliders()[HULL_MATERIAL].bounds();
+  let health = ship.ship_sections()[HULL_MATERIAL].max_health();
+  ship.hit_at(Damage::new(health, DamageType::Energy), (min+max)*0.5);
+  assert!(ship.ship_sections()[HULL_MATERIAL].is_destroyed());
+  assert_eq!(ship.hull_resistances(), Resistances::new());
+}
+
+#[test]
+fn ship_files_set_hull_material_resistances() {
+  let definition = ShipDefinition::load("./resources/ships/brew.ron").unwrap();
+  let brew = definition.to_entity(Vector2::new(0.0, 0.0));
+  let expected = Resistances::new().with_resistance(DamageType::Kinetic, 0.3)
+                                   .with_resistance(DamageType::Thermal, 0.2)
+                                   .with_resistance(DamageType::Energy, -0.1)
+                                   .with_resistance(DamageType::Collision, 0.3);
+  assert_eq!(brew.hull_resistances(), expected);
+}
+
+#[test]
+fn scenery_has_its_own_resistances() {
+  let position = Vector2::new(0.0, 0.0);
+  let mut sun: BoxEntity = Box::new(Sun::new(position));
+  let astroid: BoxEntity = Box::new(Astroid::new(position, Vector2::new(100.0, 100.0)));
+  let wall: BoxEntity = Box::new(Wall::new(position));
+  
+  assert_eq!(sun.hit(Damage::new(10.0, DamageType::Thermal)), 0.0);
+  assert!(astroid.hull_resistances().resistance(DamageType::Thermal) < 0.0);
+  assert_eq!(wall.hull_resistances().resistance(DamageType::Collision), 1.0);
+}
+
+#[test]
+fn hit_with_source_marks_attacker() {
+  let mut ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  ship.hit(Damage::new(1.0, DamageType::Thermal));
+  ship.hit(Damage::new(1.0, DamageType::Kinetic).with_source(7));
+  assert_eq!(ship.last_attacker(), Some(7));
+}
+
+#[test]
+fn shield_waits_out_delay() {
+  let mut ship = shielded_ship();
+  ship.hit(Damage::new(20.0, DamageType::Energy));
+  let shield = ship.shield();
+  
+  ship.update(SHIELD_DELAY*0.5);
+  assert_eq!(ship.shield(), shield);
+  ship.update(SHIELD_DELAY);
+  assert!(ship.shield() > shield);
+}
+
+// A broken shield lets everything through until it has fully recharged
+#[test]
+fn broken_shield_lets_hits_through() {
+  let mut ship = shielded_ship();
+  ship.hit(Damage::new(200.0, DamageType::Kinetic));
+  ship.gain_shield(50.0);
+  
+  let health = ship.health();
+  let taken = ship.hit(Damage::new(10.0, DamageType::Energy));
+  assert!(ship.is_shield_broken());
+  assert_eq!(ship.shield(), 50.0);
+  assert!(taken > 0.0 && ship.health() < health);
+  
+  for _ in 0..20 {
+    ship.update(SHIELD_DELAY);
+  }
+  let taken = ship.hit(Damage::new(10.0, DamageType::Energy));
+  assert!(!ship.is_shield_broken());
+  assert_eq!(taken, 0.0);
+}
+
+// With arcs a hit only drains the side of the shield facing it
+#[test]
+fn hits_drain_facing_arc() {
+  let mut ship = shielded_ship();
+  ship.set_shield_arcs(true);
+  ship.set_rotation(90.0);
+  ship.update(SHIELD_DELAY);
+  ship.update(SHIELD_DELAY);
+  
+  let facing = Vector2::new(-1.0, 0.0);
+  let front = ship.position() + facing*100.0;
+  let rear = ship.position() - facing*100.0;
+  assert_eq!(ship.shield_arc_at(front), ShieldArc::Front);
+  assert_eq!(ship.shield_arc_at(rear), ShieldArc::Rear);
+  
+  let (front_shield, rear_shield) = (ship.shield(), ship.rear_shield());
+  ship.hit_at(Damage::new(20.0, DamageType::Energy), rear);
+  assert_eq!(ship.shield(), front_shield);
+  assert!(ship.rear_shield() < rear_shield);
+}
@@ -0,0 +1,133 @@
+extern crate acwars;
+
+use acwars::DELTA_STEP;
+use acwars::modules::world::World;
+use acwars::modules::entities::{Ship, Astroid, BoxEntity, ShipDefinition};
+use acwars::modules::projectiles::{BoxProjectile, Gob};
+use acwars::modules::areas::{self, Level};
+use acwars::modules::gravity::{GravityField, GravityWell};
+
+use acwars::cgmath::{Vector2, InnerSpace};
+
+// A half turn under thrust takes as long as the thrusters' torque says it should, without
+// overshooting
+#[test]
+fn ship_turns_by_torque() {
+  let mut ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  let turn = 180.0;
+  let acceleration = (ship.turn_torque() / ship.moment_of_inertia()).to_degrees();
+  let max_angular_velocity = 540.0;
+  let expected = if (turn*acceleration).sqrt() <= max_angular_velocity {
+    2.0*(turn/acceleration).sqrt()
+  } else {
+    turn/max_angular_velocity + max_angular_velocity/acceleration
+  };
+  
+  ship.set_heading(turn);
+  let mut ticks = 0;
+  let mut overshoot: f32 = 0.0;
+  while ship.rotation() != turn && ticks < 100000 {
+    ship.physics(DELTA_STEP);
+    overshoot = overshoot.max(ship.rotation() - turn);
+    ticks += 1;
+  }
+  
+  let time = ticks as f32*DELTA_STEP;
+  assert!((time - expected).abs() <= expected*0.05 + 0.01, "took {}s, expected {}s", time, expected);
+  assert!(overshoot <= 0.5, "overshot by {} degrees", overshoot);
+}
+
+#[test]
+fn no_thrusters_snap_to_heading() {
+  let mut astroid: BoxEntity = Box::new(Astroid::new(Vector2::new(0.0, 0.0), Vector2::new(100.0, 100.0)));
+  astroid.set_heading(180.0);
+  astroid.physics(DELTA_STEP);
+  assert_eq!(astroid.rotation(), 180.0);
+}
+
+// Thrusters push from where they sit on the ship as drawn, so the same layout on a ship twice
+// the size turns it with twice the torque
+#[test]
+fn torque_scales_with_ship_size() {
+  let mut definition = ShipDefinition::named("Bulbz").unwrap();
+  let ship = definition.to_entity(Vector2::new(0.0, 0.0));
+  definition.size = (definition.size.0*2.0, definition.size.1*2.0);
+  let bigger = definition.to_entity(Vector2::new(0.0, 0.0));
+  
+  assert!(ship.turn_torque() > 0.0);
+  assert!((bigger.turn_torque() - ship.turn_torque()*2.0).abs() <= ship.turn_torque()*0.001);
+}
+
+// Thrust alone tops out at max velocity, turning while at it keeps the speed
+#[test]
+fn thrust_tops_out_at_max_velocity() {
+  let mut ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  let max_velocity = ship.max_velocity();
+  for tick in 0..20000 {
+    let direction = if tick < 10000 { Vector2::new(1.0, 0.0) } else { Vector2::new(0.0, 1.0) };
+    ship.apply_acceleration_in_direction(direction);
+    ship.physics(DELTA_STEP);
+    assert!(ship.velocity().magnitude() <= max_velocity + 0.001, "thrust reached {}", ship.velocity().magnitude());
+  }
+  
+  assert!(ship.velocity().y > 0.0 && ship.velocity().y > ship.velocity().x.abs());
+}
+
+// A strong enough pull drags a ship along faster than it can fly
+#[test]
+fn gravity_pulls_past_max_velocity() {
+  let mut ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  let max_velocity = ship.max_velocity();
+  for _ in 0..200 {
+    ship.pull(Vector2::new(max_velocity*20.0, 0.0));
+    ship.apply_acceleration_in_direction(Vector2::new(1.0, 0.0));
+    ship.physics(DELTA_STEP);
+  }
+  
+  assert!(ship.velocity().magnitude() > max_velocity*1.5, "only reached {}", ship.velocity().magnitude());
+}
+
+// A shot put into a circular orbit around a well stays on it for most of a lap
+#[test]
+fn shot_orbits_well() {
+  let radius = 600.0;
+  let mut projectile: BoxProjectile = Box::new(Gob::new(Vector2::new(radius, 0.0), Vector2::new(0.0, 0.0), Vector2::new(0.0, 1.0)));
+  let speed = projectile.velocity().magnitude();
+  let mut field = GravityField::new();
+  field.add(1, Vector2::new(0.0, 0.0), GravityWell::new(speed*speed*radius));
+  
+  let mut closest: f32 = radius;
+  let mut furthest: f32 = radius;
+  let mut swept = 0.0;
+  let mut last = projectile.position();
+  for _ in 0..4000 {
+    let acceleration = field.acceleration_at(projectile.position(), 0);
+    projectile.pull(acceleration);
+    projectile.update(DELTA_STEP);
+    
+    let position = projectile.position();
+    closest = closest.min(position.magnitude());
+    furthest = furthest.max(position.magnitude());
+    swept += last.angle(position).0.abs();
+    last = position;
+  }
+  
+  assert!(projectile.should_exist());
+  assert!(closest >= radius*0.98 && furthest <= radius*1.02, "orbit strayed between {} and {}", closest, furthest);
+  assert!(swept.to_degrees() >= 270.0, "only swept {} degrees", swept.to_degrees());
+}
+
+// The default level's Sun only pulls while its area has gravity on
+#[test]
+fn gravity_toggles_per_area() {
+  let level = Level::load_named(areas::DEFAULT_LEVEL).unwrap();
+  for &enabled in &[true, false] {
+    let mut level_areas = level.areas().unwrap();
+    for area in &mut level_areas {
+      area.set_gravity(enabled);
+    }
+    
+    let world = World::new(level_areas, Box::new(Ship::new(Vector2::new(0.0, 0.0))), Vector2::new(1280.0, 1080.0));
+    assert_eq!(world.gravity_field().is_empty(), !enabled);
+  }
+}
@@ -0,0 +1,232 @@
+extern crate acwars;
+
+use acwars::DELTA_STEP;
+use acwars::modules::world::World;
+use acwars::modules::entities::{Ship, Brew, BoxEntity, FullEntity, Hostility};
+use acwars::modules::abilities::{self, Ability, BoxAbility, Loadout, SingleShot};
+use acwars::modules::controllers::AbilitySpamAi;
+use acwars::modules::player::{Input, Refit};
+use acwars::modules::projectiles::{BoxProjectile, Ftpl};
+use acwars::modules::damage::{Damage, DamageType};
+
+use acwars::cgmath::{Vector2, InnerSpace};
+
+const HULL: usize = 0;
+const LEFT_THRUSTER: usize = 3;
+const RIGHT_THRUSTER: usize = 4;
+const WEAPON_MOUNT: usize = 5;
+const REACTOR: usize = 6;
+
+fn window_size() -> Vector2<f32> {
+  Vector2::new(1280.0, 1080.0)
+}
+
+fn textures(abilities: &Vec<BoxAbility>) -> Vec<String> {
+  abilities.iter().map(|ability| ability.texture()).collect()
+}
+
+fn named(names: &[&str]) -> Vec<String> {
+  names.iter().map(|name| abilities::ability_by_name(name).unwrap().texture()).collect()
+}
+
+// Enough damage to destroy the section, landed on its centre
+fn destroy_section(ship: &mut BoxEntity, section: usize) {
+  let (min, max) = ship.section_colliders()[section].bounds();
+  let health = ship.ship_sections()[section].max_health();
+  ship.hit_at(Damage::new(health, DamageType::Energy), (min+max)*0.5);
+}
+
+// Shoots each of a turned ship's thrusters and its weapon mount from outside, and checks only
+// that section is damaged
+#[test]
+fn shots_land_on_their_section() {
+  let mut ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)).as_hostile());
+  ship.set_rotation(90.0);
+  
+  for &i in &[LEFT_THRUSTER, RIGHT_THRUSTER, WEAPON_MOUNT] {
+    let (min, max) = ship.section_colliders()[i].bounds();
+    let centre = (min+max)*0.5;
+    let outwards = (centre - ship.position()).normalize();
+    
+    let mut projectile: BoxProjectile = Box::new(Ftpl::new(centre + outwards*1000.0, Vector2::new(0.0, 0.0), -outwards));
+    let factor = 2000.0/DELTA_STEP/projectile.velocity().magnitude();
+    projectile.multiply_velocity(factor);
+    projectile.physics(DELTA_STEP);
+    
+    let mut target = ship.clone();
+    projectile.collide_with(&mut target);
+    let damaged: Vec<usize> = (0..ship.ship_sections().len()).filter(|&j| {
+      target.ship_sections()[j].health() < ship.ship_sections()[j].health()
+    }).collect();
+    
+    assert_eq!(damaged, vec!(i));
+  }
+}
+
+#[test]
+fn losing_thrusters_cuts_thrust_and_torque() {
+  let ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  let mut damaged = ship.clone();
+  
+  destroy_section(&mut damaged, LEFT_THRUSTER);
+  assert!(damaged.ship_sections()[LEFT_THRUSTER].is_destroyed());
+  assert!((damaged.thrust_fraction() - 0.5).abs() < 0.001);
+  assert!((damaged.turn_torque() - ship.turn_torque()*0.5).abs() < 0.001);
+  
+  destroy_section(&mut damaged, RIGHT_THRUSTER);
+  assert_eq!(damaged.thrust_fraction(), 0.0);
+  assert_eq!(damaged.turn_torque(), 0.0);
+  
+  damaged.set_velocity(Vector2::new(0.0, 0.0));
+  damaged.add_acceleration(Vector2::new(0.0, 1.0));
+  damaged.physics(DELTA_STEP);
+  assert_eq!(damaged.velocity().magnitude(), 0.0);
+}
+
+#[test]
+fn losing_the_hull_kills_the_ship() {
+  let mut ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  let hull_health = ship.ship_sections()[HULL].max_health();
+  assert!(hull_health < ship.health());
+  
+  let position = ship.position();
+  ship.hit_at(Damage::new(hull_health, DamageType::Energy), position);
+  assert!(!ship.should_exist());
+}
+
+#[test]
+fn player_abilities_come_from_slots() {
+  let world = World::new(Vec::new(), Box::new(Ship::new(Vector2::new(0.0, 0.0))), window_size());
+  let expected = named(&["NoAbility", "Move", "Shield", "DoubleShot", "Dash", "Laser", "Haste", "SingleShot"]);
+  assert_eq!(textures(&world.input().lock().all_abilities()), expected);
+}
+
+// Installing in both linked wings fires them together and keeps their cooldown through a sync,
+// then clearing one with a refit takes it away
+#[test]
+fn linked_slots_fire_together() {
+  let mut ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  let mut loadout = Loadout::new();
+  loadout.sync(&ship);
+  let before = loadout.len();
+  
+  Refit::new(1, "SingleShot").apply(&mut ship);
+  Refit::new(2, "SingleShot").apply(&mut ship);
+  loadout.sync(&ship);
+  assert_eq!(loadout.len(), before+2);
+  
+  let wing = loadout.slot_index(8).unwrap();
+  assert_eq!(loadout.linked(wing).len(), 2);
+  loadout.activate(wing, &mut ship, Vector2::new(0.0, 1000.0), window_size(), &Hostility::Friendly);
+  assert_eq!(ship.return_projectiles().len(), 2);
+  
+  loadout.sync(&ship);
+  assert!(loadout.ability(wing).unwrap().percentage_cooldown_left() > 0.0, "the wing's cooldown was lost on sync");
+  
+  Refit::new(1, "NoAbility").apply(&mut ship);
+  loadout.sync(&ship);
+  assert_eq!(loadout.len(), before+1);
+  assert!(!ship.modular_slots().iter().any(|slot| slot.id() == 8 && slot.has_ability()));
+}
+
+// Keys stay on their slot when the abilities before it drop out, and a lost slot gives nothing
+#[test]
+fn bindings_follow_their_slot() {
+  let mut ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  let mut input = Input::new();
+  input.sync(&ship);
+  let before = input.get_ability_textures();
+  
+  destroy_section(&mut ship, WEAPON_MOUNT);
+  input.sync(&ship);
+  let expected = named(&["Dash", "NoAbility", "Haste", "NoAbility", "Move", "Shield", "NoAbility"]);
+  assert_eq!(before[0], expected[0]);
+  assert_eq!(input.get_ability_textures(), expected);
+}
+
+#[test]
+fn bindings_only_take_the_ships_slots() {
+  let ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  let mut input = Input::new();
+  input.set_bindings([Some(8), Some(42), None, Some(1), Some(-1), Some(9), Some(3)], &ship);
+  assert_eq!(input.bindings(), [Some(8), None, None, Some(1), None, Some(9), Some(3)]);
+}
+
+#[test]
+fn destroyed_section_loses_its_abilities() {
+  let mut ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  let mut loadout = Loadout::new();
+  
+  destroy_section(&mut ship, WEAPON_MOUNT);
+  loadout.sync(&ship);
+  let lost = named(&["DoubleShot", "Laser"]);
+  assert!(!textures(loadout.abilities()).iter().any(|texture| lost.contains(texture)));
+}
+
+#[test]
+fn ai_refits_its_ship() {
+  let brew = FullEntity::new(Box::new(AbilitySpamAi::new().with_ability(Box::new(SingleShot::new()))), Box::new(Brew::new(Vector2::new(0.0, 0.0))));
+  let refitted: Vec<BoxAbility> = brew.entity.lock().ability_slots().iter().filter_map(|slot| slot.ability()).collect();
+  assert_eq!(textures(&refitted), vec!(SingleShot::new().texture()));
+}
+
+// An ability won't fire without the energy to pay for it, and does once enough is regained
+#[test]
+fn abilities_cost_energy() {
+  let target = Vector2::new(0.0, 1000.0);
+  let mut ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  let energy = ship.energy();
+  ship.drain_energy(energy);
+  
+  let mut shot: BoxAbility = Box::new(SingleShot::new());
+  shot.activate(&mut ship, target, window_size(), &Hostility::Friendly);
+  assert!(ship.return_projectiles().is_empty());
+  
+  ship.update(shot.energy_cost()/ship.energy_generation());
+  shot.activate(&mut ship, target, window_size(), &Hostility::Friendly);
+  assert_eq!(ship.return_projectiles().len(), 1);
+  assert!(ship.energy() <= 0.001);
+}
+
+#[test]
+fn thrusters_draw_energy() {
+  let mut thrusting: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  let before = thrusting.energy();
+  thrusting.add_acceleration(Vector2::new(0.0, 1.0));
+  thrusting.physics(0.1);
+  assert!((before - thrusting.energy() - thrusting.power_draw()*0.1).abs() < 0.001);
+  
+  let mut drifting: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  let energy = drifting.energy();
+  drifting.drain_energy(energy);
+  drifting.add_acceleration(Vector2::new(0.0, 1.0));
+  drifting.physics(0.1);
+  assert_eq!(drifting.velocity().magnitude(), 0.0);
+}
+
+// On an empty pool the shield only regains what the ship generates
+#[test]
+fn shields_draw_energy() {
+  let mut shielded: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  shielded.set_max_shield(1000.0);
+  shielded.set_shield_regen(1000.0);
+  let energy = shielded.energy();
+  shielded.drain_energy(energy);
+  
+  let shield = shielded.shield();
+  shielded.update(0.1);
+  let generated = shielded.energy_generation()*0.1;
+  assert!((shielded.shield() - shield - generated).abs() < 0.001);
+  assert!(shielded.energy() <= 0.001);
+}
+
+#[test]
+fn losing_the_reactor_cuts_generation() {
+  let mut ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  let generation = ship.energy_generation();
+  
+  destroy_section(&mut ship, REACTOR);
+  let lost = generation - ship.energy_generation();
+  assert!(lost > 0.0);
+  assert!((lost - ship.ship_sections()[REACTOR].generation()).abs() < 0.001);
+}
@@ -0,0 +1,39 @@
+extern crate acwars;
+
+use acwars::modules::entities::{self, Ship, Brew, BoxEntity, ShipDefinition, SHIP_DIRECTORY};
+
+use acwars::cgmath::Vector2;
+
+use std::fs;
+
+#[test]
+fn every_ship_file_loads() {
+  let files = fs::read_dir(SHIP_DIRECTORY).unwrap()
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().extension().map(|e| e == "ron").unwrap_or(false))
+                .count();
+  
+  assert_eq!(ShipDefinition::load_all(SHIP_DIRECTORY).len(), files);
+}
+
+// Ship and Brew are only names for the ships in bulbz.ron and brew.ron
+#[test]
+fn built_in_ships_come_from_their_files() {
+  let position = Vector2::new(0.0, 0.0);
+  let ship: BoxEntity = Box::new(Ship::new(position));
+  let brew: BoxEntity = Box::new(Brew::new(position));
+  for (ship, name) in vec!((ship, "Bulbz"), (brew, "Brew")) {
+    let defined = ShipDefinition::named(name).unwrap().to_entity(position);
+    assert_eq!(ship.name(), name);
+    assert_eq!(ship.texture(), defined.texture());
+    assert_eq!(ship.max_health(), defined.max_health());
+    assert_eq!(ship.collision_information(), defined.collision_information());
+  }
+}
+
+#[test]
+fn selectable_ships_are_found_by_name() {
+  for ship in entities::selectable_ships() {
+    assert_eq!(entities::selectable_ship(&ship.name()).unwrap().name(), ship.name());
+  }
+}
@@ -0,0 +1,70 @@
+extern crate acwars;
+
+use acwars::modules::entities::{Ship, Brew, BoxEntity};
+use acwars::modules::controllers::{EntityController, StateMachineAi, AiState};
+use acwars::modules::damage::{Damage, DamageType};
+
+use acwars::cgmath::Vector2;
+
+const DELTA_TIME: f32 = 0.1;
+
+fn area_size() -> Vector2<f32> {
+  Vector2::new(2000.0, 2000.0)
+}
+
+fn window_size() -> Vector2<f32> {
+  Vector2::new(1280.0, 720.0)
+}
+
+// Knocks the ship down below its flee health and leaves it at the centre of its area with the
+// target far away, so it flees straight into retreating
+fn retreat(ship: &mut BoxEntity, ai: &mut StateMachineAi, ticks: u32) {
+  while ship.health() > ship.max_health()*0.2 {
+    ship.hit(Damage::new(10.0, DamageType::Energy));
+  }
+  
+  let target = Vector2::new(5000.0, 0.0);
+  for _ in 0..ticks {
+    ai.update(ship, Some(target), Vector2::new(0.0, 0.0), area_size(), window_size(), DELTA_TIME);
+  }
+}
+
+#[test]
+fn brew_repairs_at_home() {
+  let mut ship: BoxEntity = Box::new(Brew::new(Vector2::new(0.0, 0.0)));
+  let mut ai = StateMachineAi::new();
+  
+  retreat(&mut ship, &mut ai, 2);
+  assert_eq!(ai.state(), AiState::Retreat);
+  let health = ship.health();
+  
+  for _ in 0..10 {
+    ai.update(&mut ship, Some(Vector2::new(5000.0, 0.0)), Vector2::new(0.0, 0.0), area_size(), window_size(), DELTA_TIME);
+  }
+  assert_eq!(ai.state(), AiState::Retreat);
+  assert!(ship.health() > health);
+}
+
+#[test]
+fn no_repair_bay_no_repairs() {
+  let mut ship: BoxEntity = Box::new(Ship::new(Vector2::new(0.0, 0.0)));
+  assert_eq!(ship.repair_rate(), 0.0);
+  
+  let mut ai = StateMachineAi::new();
+  retreat(&mut ship, &mut ai, 2);
+  let health = ship.health();
+  
+  for _ in 0..10 {
+    ai.update(&mut ship, Some(Vector2::new(5000.0, 0.0)), Vector2::new(0.0, 0.0), area_size(), window_size(), DELTA_TIME);
+  }
+  assert_eq!(ship.health(), health);
+}
+
+#[test]
+fn spawns_start_inside_their_area() {
+  let mut ship: BoxEntity = Box::new(Brew::new(Vector2::new(-700.0, 650.0)));
+  let mut ai = StateMachineAi::new();
+  
+  ai.update(&mut ship, Some(Vector2::new(-100.0, 0.0)), Vector2::new(0.0, 0.0), area_size(), window_size(), DELTA_TIME);
+  assert_eq!(ai.state(), AiState::Pursue);
+}
@@ -0,0 +1,76 @@
+extern crate acwars;
+
+use acwars::modules::entities::{Brew, BoxEntity, Hostility};
+use acwars::modules::controllers::{EntityController, AbilitySpamAi, TargetView};
+use acwars::modules::abilities::SingleShot;
+use acwars::modules::scripting::EntitySnapshot;
+
+use acwars::cgmath::Vector2;
+
+const PLAYER_ID: u32 = 1;
+const DELTA_TIME: f32 = 0.1;
+
+fn snapshot(entity: &BoxEntity) -> EntitySnapshot {
+  EntitySnapshot {
+    id: entity.id(),
+    area: Some(0),
+    position: entity.position(),
+    velocity: entity.velocity(),
+    rotation: entity.rotation(),
+    health: entity.health(),
+    max_health: entity.max_health(),
+    hostility: entity.hostility().clone(),
+    last_attacker: entity.last_attacker(),
+  }
+}
+
+fn view(ship: &BoxEntity) -> TargetView {
+  let player = EntitySnapshot {
+    id: PLAYER_ID,
+    area: None,
+    position: Vector2::new(300.0, 0.0),
+    velocity: Vector2::new(0.0, 0.0),
+    rotation: 0.0,
+    health: 100.0,
+    max_health: 100.0,
+    hostility: Hostility::Friendly,
+    last_attacker: None,
+  };
+  
+  TargetView::new(vec!(snapshot(ship), player), PLAYER_ID)
+}
+
+// Runs the ship's controller for a tick and returns how many projectiles it fired
+fn tick(ship: &mut BoxEntity, ai: &mut AbilitySpamAi) -> usize {
+  let target = ai.select_target(&*ship, &view(ship));
+  ai.update(ship, target, Vector2::new(0.0, 0.0), Vector2::new(2000.0, 2000.0), Vector2::new(1280.0, 720.0), DELTA_TIME);
+  
+  let (_, projectiles) = ship.update(DELTA_TIME);
+  projectiles.len()
+}
+
+#[test]
+fn neutral_ignores_player_until_hit() {
+  let mut ship: BoxEntity = Box::new(Brew::new(Vector2::new(0.0, 0.0)));
+  ship.set_id(2);
+  ship.set_hostility(Hostility::Neutral);
+  let mut ai = AbilitySpamAi::new().with_ability(Box::new(SingleShot::new()));
+  
+  assert!(ai.select_target(&ship, &view(&ship)).is_none());
+  for _ in 0..20 {
+    assert_eq!(tick(&mut ship, &mut ai), 0);
+  }
+  
+  ship.attacked_by(PLAYER_ID);
+  assert_eq!(ai.select_target(&ship, &view(&ship)), Some(Vector2::new(300.0, 0.0)));
+  assert!(tick(&mut ship, &mut ai) > 0);
+}
+
+#[test]
+fn hostile_aims_at_the_player() {
+  let mut ship: BoxEntity = Box::new(Brew::new(Vector2::new(0.0, 0.0)).as_hostile());
+  ship.set_id(2);
+  let mut ai = AbilitySpamAi::new();
+  
+  assert_eq!(ai.select_target(&ship, &view(&ship)), Some(Vector2::new(300.0, 0.0)));
+}
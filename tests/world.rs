@@ -0,0 +1,180 @@
+extern crate acwars;
+extern crate hlua;
+extern crate rand;
+
+use acwars::DELTA_STEP;
+use acwars::modules::world::World;
+use acwars::modules::entities::Ship;
+use acwars::modules::areas;
+use acwars::modules::player::{InputState, Refit};
+use acwars::modules::replay::Replay;
+
+use acwars::cgmath::Vector2;
+
+use hlua::Lua;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+const SEED: u64 = 645;
+const TICKS: u64 = 2000;
+
+fn window_size() -> Vector2<f32> {
+  Vector2::new(1280.0, 1080.0)
+}
+
+fn new_world() -> World {
+  World::battle(areas::DEFAULT_LEVEL, Box::new(Ship::new(Vector2::new(0.0, 0.0))), window_size()).unwrap()
+                .with_seed(SEED).deterministic()
+}
+
+fn new_lua() -> Lua<'static> {
+  let mut lua = Lua::new();
+  lua.openlibs();
+  lua
+}
+
+// Random inputs changing every 250 ticks
+fn scripted_inputs() -> Vec<InputState> {
+  let mut rng = StdRng::seed_from_u64(SEED);
+  let mut inputs = Vec::new();
+  let mut state = InputState::new();
+  for tick in 0..TICKS {
+    if tick % 250 == 0 {
+      state.mouse_pos = Vector2::new(rng.gen_range(0.0, 1280.0), rng.gen_range(0.0, 1080.0));
+      state.left_mouse = rng.gen_bool(0.5);
+      state.right_mouse = rng.gen_bool(0.5);
+      state.q_pressed = rng.gen_bool(0.1);
+      state.e_pressed = rng.gen_bool(0.1);
+    }
+    inputs.push(state);
+  }
+  
+  inputs
+}
+
+fn run(world: &mut World, inputs: &Vec<InputState>) {
+  let mut lua = new_lua();
+  for input_state in inputs {
+    world.update(input_state, Some(&mut lua), DELTA_STEP);
+  }
+}
+
+fn bits(world: &World) -> Vec<[u32; 5]> {
+  world.entity_states().iter().map(|(position, velocity, health)| {
+    [position.x.to_bits(), position.y.to_bits(), velocity.x.to_bits(), velocity.y.to_bits(), health.to_bits()]
+  }).collect()
+}
+
+#[test]
+fn same_seed_same_world() {
+  let inputs = scripted_inputs();
+  let mut first = new_world();
+  let mut second = new_world();
+  run(&mut first, &inputs);
+  run(&mut second, &inputs);
+  
+  assert_eq!(first.ticks(), second.ticks());
+  assert_eq!(bits(&first), bits(&second));
+}
+
+#[test]
+fn replay_matches_recording() {
+  let mut recorded = new_world().with_recording();
+  run(&mut recorded, &scripted_inputs());
+  
+  let path = std::env::temp_dir().join("acwars_replay_matches_recording.replay");
+  recorded.recording().unwrap().save(&path).unwrap();
+  let mut replay = Replay::load(&path).unwrap();
+  let _ = std::fs::remove_file(&path);
+  
+  let mut replayed = new_world();
+  let mut lua = new_lua();
+  while let Some(tick) = replay.next_input() {
+    replayed.replay_tick(&tick, Some(&mut lua), DELTA_STEP);
+  }
+  
+  assert_eq!(replay.ship(), "Bulbz");
+  assert_eq!(replayed.ticks(), recorded.ticks());
+  assert_eq!(bits(&replayed), bits(&recorded));
+}
+
+// A refit from the module viewer lands on the tick it was queued, and lands there again when
+// the saved replay is played back
+#[test]
+fn refits_are_replayed() {
+  let inputs = scripted_inputs();
+  let mut recorded = new_world().with_recording();
+  let mut lua = new_lua();
+  for (tick, input_state) in inputs.iter().enumerate() {
+    if tick == 500 {
+      recorded.refits().lock().push(Refit::new(1, "Laser"));
+    }
+    recorded.update(input_state, Some(&mut lua), DELTA_STEP);
+  }
+  
+  let path = std::env::temp_dir().join("acwars_refits_are_replayed.replay");
+  recorded.recording().unwrap().save(&path).unwrap();
+  let mut replay = Replay::load(&path).unwrap();
+  let _ = std::fs::remove_file(&path);
+  
+  let mut replayed = new_world();
+  let mut lua = new_lua();
+  while let Some(tick) = replay.next_input() {
+    replayed.replay_tick(&tick, Some(&mut lua), DELTA_STEP);
+  }
+  
+  for world in &[&recorded, &replayed] {
+    assert!(world.ship().lock().modular_slots().iter().any(|slot| slot.id() == 8 && slot.has_ability()));
+  }
+  assert_eq!(bits(&replayed), bits(&recorded));
+}
+
+// Scripts can spawn ships from resources/ships and look them up by the id they were given
+#[test]
+fn script_spawns_defined_ship() {
+  let script = "spawned = spawn_entity(0, \"Bulbz\", 0, 0, \"Hostile\", \"Idle\", \"\")".to_string();
+  let mut world = new_world().with_script(script);
+  let mut lua = new_lua();
+  world.update(&InputState::new(), Some(&mut lua), DELTA_STEP);
+  
+  let id: u32 = lua.get("spawned").unwrap();
+  let spawned = world.entity_by_id(id).unwrap();
+  assert_eq!(spawned.lock().texture(), "Bulbz");
+}
+
+// What scripts roll comes from the world's rng, so it follows the seed
+#[test]
+fn script_random_follows_the_seed() {
+  let roll = |seed: u64| -> f32 {
+    let script = "rolled = random(0, 1000)".to_string();
+    let mut world = new_world().with_seed(seed).with_script(script);
+    let mut lua = new_lua();
+    world.update(&InputState::new(), Some(&mut lua), DELTA_STEP);
+    lua.get("rolled").unwrap()
+  };
+  
+  assert_eq!(roll(SEED), roll(SEED));
+  assert_ne!(roll(SEED), roll(SEED+1));
+}
+
+#[test]
+fn math_random_rolls_whole_numbers_in_range() {
+  let script = "
+    rolls = {}
+    for i = 1, 100 do rolls[i] = math.random(1, 6) end
+    low, high = 6, 1
+    for i = 1, 100 do
+      if rolls[i] ~= math.floor(rolls[i]) then error('not whole') end
+      if rolls[i] < low then low = rolls[i] end
+      if rolls[i] > high then high = rolls[i] end
+    end".to_string();
+  let mut world = new_world().with_script(script);
+  let mut lua = new_lua();
+  world.update(&InputState::new(), Some(&mut lua), DELTA_STEP);
+  
+  let low: i32 = lua.get("low").unwrap();
+  let high: i32 = lua.get("high").unwrap();
+  assert!(low >= 1 && high <= 6);
+  assert_eq!((low, high), (1, 6));
+}